@@ -11,6 +11,10 @@ pub const ALL: Bitboard = Bitboard(!0);
 pub const EMPTY: Bitboard = Bitboard(0);
 pub const NOT_A_FILE: Bitboard = Bitboard(0x7f7f7f7f7f7f7f7f);
 pub const NOT_H_FILE: Bitboard = Bitboard(0xfefefefefefefefe);
+/// The light squares, like h1 and a8
+pub const LIGHT_SQUARES: Bitboard = Bitboard(0x55aa55aa55aa55aa);
+/// The dark squares, like a1 and h8
+pub const DARK_SQUARES: Bitboard = Bitboard(0xaa55aa55aa55aa55);
 
 // Should make new method instead of public access to value
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
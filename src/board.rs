@@ -3,25 +3,38 @@ use std::{
     fmt::{self, Display},
     hash::Hash,
     str::FromStr,
+    sync::Arc,
 };
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    move_gen, Bitboard, BoardBuilder, BoardError, Castle, Check, Color, Move, MoveState, Piece,
-    Ray, Square, ALL, EMPTY,
+    move_gen, Bitboard, BoardBuilder, BoardError, Castle, Check, Color, DrawReason, Move,
+    MoveState, Outcome, Piece, Ray, Square, Variant, ZobristHash, ZobristKeys, ALL, EMPTY,
 };
 
+use self::hash::StoredHash;
+
 use self::modify::Modifier;
 
 mod attacks;
 pub mod builder;
-mod hash;
+mod destinations;
+mod draw;
+pub mod fog;
+mod gives_check;
+pub mod hash;
 mod index;
+mod legality;
 mod make;
 mod modify;
 mod perft;
+pub mod position;
+pub mod premove;
+mod repetition;
+mod san;
+mod see;
 
 #[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -31,17 +44,25 @@ pub struct Board {
     attacks: Bitboard,
     pins: Bitboard,
     check: Check,
+    /// Squares from which each kind of piece of the side to move would attack the enemy king
+    check_squares: [Bitboard; 6],
+    /// Pieces of the side to move that give discovered check by moving off their line
+    discovered_blockers: Bitboard,
     color_to_move: Color,
     #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
     pieces: [Piece; 64],
     castle: [Castle; 2],
     ep_target: Option<Square>,
+    variant: Variant,
     halfmove: u32,
     fullmove: u32,
     move_history: Vec<MoveState>,
-    hash: u64,
-    #[cfg_attr(feature = "serde", serde(skip, default = "hash::zobrist_keys"))]
-    hash_keys: [u64; 781],
+    hash: StoredHash,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default = "hash::default_keys", with = "hash::serde_keys")
+    )]
+    keys: Arc<ZobristKeys>,
 }
 
 pub type BoardIter = std::array::IntoIter<Piece, 64>;
@@ -97,11 +118,16 @@ impl default::Default for Board {
 }
 
 impl PartialEq for Board {
+    /// Boards are equal if their pieces, side to move, castling rights, en passant target and
+    /// variant are, and they are hashed with equal [ZobristKeys]. The counters and move history
+    /// aren't compared.
     fn eq(&self, other: &Self) -> bool {
         self.bitboards == other.bitboards
             && self.color_to_move == other.color_to_move
             && self.castle == other.castle
             && self.ep_target == other.ep_target
+            && self.variant == other.variant
+            && self.keys == other.keys
     }
 }
 
@@ -145,27 +171,34 @@ impl Board {
             attacks: EMPTY,
             pins: EMPTY,
             check: Check::None,
+            check_squares: [EMPTY; 6],
+            discovered_blockers: EMPTY,
             pieces: [Piece::Empty; 64],
             color_to_move: Color::White,
             castle: [Castle::None; 2],
             ep_target: None,
+            variant: Variant::Standard,
             halfmove: 0,
             fullmove: 1,
             move_history: Vec::new(),
             hash: 0,
-            hash_keys: hash::zobrist_keys(),
+            keys: ZobristKeys::default_keys().clone(),
         }
     }
 
     #[inline]
     fn modify<'a, T>(&'a mut self, arg: impl FnOnce(&mut Modifier<'a>) -> T) -> T {
-        let mut action = Modifier { board: self };
+        let mut action = Modifier {
+            hashed_ep_target: hash::hashed_ep_target(self),
+            board: self,
+        };
         let response = arg(&mut action);
         action.complete();
         response
     }
 
-    /// Creates a representation of the board in Forsynth-Edwards Notation(FEN)
+    /// Creates a representation of the board in Forsynth-Edwards Notation(FEN). Boards of a
+    /// variant other than Standard name it after the counters, as described for [Variant].
     pub fn to_fen(&self) -> String {
         let mut output = String::new();
 
@@ -207,6 +240,7 @@ impl Board {
 
         output += &format!(" {}", self.halfmove);
         output += &format!(" {}", self.fullmove);
+        self.variant.write_fen(&mut output);
 
         output
     }
@@ -218,14 +252,86 @@ impl Board {
         self.color_to_move
     }
 
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
     pub fn legal_moves(&self) -> Vec<Move> {
         move_gen::legal(self)
     }
 
-    pub fn king(&self, color: Color) -> Square {
-        self[Piece::king(color)]
-            .first_square()
-            .expect("King is not on the board")
+    /// Returns the [Outcome] of the game if it has ended, or `None` if play can go on. Besides the
+    /// rules of the variant, this covers the draws that FIDE rules make automatic: insufficient
+    /// material, the 75-move rule and fivefold repetition. A checkmate on the move that reaches
+    /// the 75-move limit still counts as checkmate. Draws a player has to claim are returned by
+    /// [Board::claimable_draw] instead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color, Outcome, WinReason};
+    /// let mut board = Board::default();
+    /// assert_eq!(board.outcome(), None);
+    ///
+    /// // Fool's mate
+    /// for mv in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+    ///     board.make(mv.parse()?)?;
+    /// }
+    /// assert_eq!(
+    ///     board.outcome(),
+    ///     Some(Outcome::Win { winner: Color::Black, reason: WinReason::Checkmate })
+    /// );
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn outcome(&self) -> Option<Outcome> {
+        if let Some(outcome) = self.variant.outcome(self) {
+            return Some(outcome);
+        }
+        let reason = if self.halfmove >= 150 {
+            DrawReason::SeventyFiveMoveRule
+        } else if self.is_repetition(5) {
+            DrawReason::FivefoldRepetition
+        } else if self.is_insufficient_material() {
+            DrawReason::InsufficientMaterial
+        } else {
+            return None;
+        };
+        Some(Outcome::Draw(reason))
+    }
+
+    /// Returns the draw the side to move may claim, or `None` if there is none. This is a
+    /// threefold repetition, or 50 moves by each side without a capture or pawn move. A game that
+    /// has already ended can't be claimed drawn.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, DrawReason};
+    /// let mut board = Board::default();
+    /// for _ in 0..2 {
+    ///     for mv in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+    ///         board.make(mv.parse()?)?;
+    ///     }
+    /// }
+    /// assert_eq!(board.claimable_draw(), Some(DrawReason::ThreefoldRepetition));
+    /// assert_eq!(board.outcome(), None);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn claimable_draw(&self) -> Option<DrawReason> {
+        if self.outcome().is_some() {
+            return None;
+        }
+        if self.halfmove >= 100 {
+            Some(DrawReason::FiftyMoveRule)
+        } else if self.is_repetition(3) {
+            Some(DrawReason::ThreefoldRepetition)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the [Square] of the king of the given [Color], or `None` if that side has no king.
+    /// If there are multiple kings, as is possible in Antichess, the first one is returned.
+    pub fn king(&self, color: Color) -> Option<Square> {
+        self[Piece::king(color)].first_square()
     }
 
     pub fn pin_on_square(&self, square: Square) -> Option<Ray> {
@@ -233,7 +339,7 @@ impl Board {
         match piece {
             Piece::Filled(_, color) => {
                 if self.pins.contains(square) {
-                    Ray::from(self.king(color), square)
+                    Ray::from(self.king(color)?, square)
                 } else {
                     None
                 }
@@ -262,7 +368,42 @@ impl Board {
         self.castle[color]
     }
 
-    pub fn hash(&self) -> u64 {
+    /// The 64-bit Zobrist hash
+    pub fn hash(&self) -> ZobristHash {
+        self.hash as ZobristHash
+    }
+
+    /// The 128-bit Zobrist hash, whose low 64 bits are [Board::hash]. Repetitions are found
+    /// with all 128 bits.
+    #[cfg(feature = "zobrist128")]
+    pub fn hash128(&self) -> crate::ZobristHash128 {
         self.hash
     }
+
+    /// The [ZobristKeys] the board is hashed with
+    pub fn zobrist_keys(&self) -> &Arc<ZobristKeys> {
+        &self.keys
+    }
+
+    /// Hashes the board with other [ZobristKeys] from now on. The current position and every
+    /// earlier one in the move history are hashed again, so repetitions are still found.
+    pub fn set_zobrist_keys(&mut self, keys: Arc<ZobristKeys>) {
+        let mut earlier = self.clone();
+        for ms in self.move_history.iter_mut().rev() {
+            earlier.unmake();
+            ms.hash = hash::stored(keys.hash128(&earlier));
+        }
+        self.hash = hash::stored(keys.hash128(self));
+        self.keys = keys;
+    }
+
+    /// The number of moves since the last capture or pawn move
+    pub fn halfmove(&self) -> u32 {
+        self.halfmove
+    }
+
+    /// The number of the current full move, starting at 1 and going up after Black moves
+    pub fn fullmove(&self) -> u32 {
+        self.fullmove
+    }
 }
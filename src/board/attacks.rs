@@ -1,4 +1,5 @@
-use crate::{Piece, Color, Bitboard, Dir, ALL_DIRS, NOT_A_FILE, NOT_H_FILE, EMPTY, Check};
+use crate::{Piece, PieceKind, Color, Bitboard, Dir, ALL_DIRS, NOT_A_FILE, NOT_H_FILE, EMPTY, Check, Square};
+use crate::dir::{BISHOP_DIRS, ROOK_DIRS};
 use super::Board;
 
 
@@ -12,6 +13,7 @@ impl Board {
     #[inline]
     pub(super) fn update_position(&mut self) {
         self.update_pins_and_checks();
+        self.update_check_info();
         self.attacks = self.gen_attacks(!self.color_to_move);
     }
 
@@ -108,13 +110,146 @@ impl Board {
         output
     }
 
+    /// Returns the pieces of both colors that attack the given square. Only pieces in `occupancy`
+    /// count as attackers or as blockers, so removing a piece from it reveals any slider behind it.
+    /// Mask the result with `board[color]` for one side's attackers.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Bitboard, Board, Piece, Square};
+    /// let board = Board::from_fen("4k3/8/8/3p4/8/8/3R4/3QK3 w - - 0 1")?;
+    /// let d5: Square = "d5".parse()?;
+    /// let rook: Bitboard = "d2".parse::<Square>()?.into();
+    /// let queen: Bitboard = "d1".parse::<Square>()?.into();
+    /// let occupancy = !board[Piece::Empty];
+    ///
+    /// // The queen is behind the rook, so it only attacks d5 once the rook is gone
+    /// assert_eq!(board.attackers_to(d5, occupancy), rook);
+    /// assert_eq!(board.attackers_to(d5, occupancy ^ rook), queen);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    #[inline]
+    pub fn attackers_to(&self, square: Square, occupancy: Bitboard) -> Bitboard {
+        let initial: Bitboard = square.into();
+        let diagonal = self[Piece::bishop(Color::White)]
+            | self[Piece::bishop(Color::Black)]
+            | self[Piece::queen(Color::White)]
+            | self[Piece::queen(Color::Black)];
+        let orthogonal = self[Piece::rook(Color::White)]
+            | self[Piece::rook(Color::Black)]
+            | self[Piece::queen(Color::White)]
+            | self[Piece::queen(Color::Black)];
+
+        // A pawn attacks the square if a pawn of the other color on it would attack the pawn
+        let attackers = self.pawn_attacks(initial, Color::White) & self[Piece::pawn(Color::Black)]
+            | self.pawn_attacks(initial, Color::Black) & self[Piece::pawn(Color::White)]
+            | self.knight_attacks(initial)
+                & (self[Piece::knight(Color::White)] | self[Piece::knight(Color::Black)])
+            | self.king_attacks(initial)
+                & (self[Piece::king(Color::White)] | self[Piece::king(Color::Black)])
+            | slider_attacks(initial, occupancy, &BISHOP_DIRS) & diagonal
+            | slider_attacks(initial, occupancy, &ROOK_DIRS) & orthogonal;
+        attackers & occupancy
+    }
+
+    /// Returns every square the pieces of the given [Color] attack, whether it is empty or holds a
+    /// piece of either color. Unlike [Board::attacks], sliders stop at the enemy king.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color};
+    /// let board = Board::default();
+    /// assert_eq!(board.attacks_by(Color::White).count_squares(), 22);
+    /// ```
+    pub fn attacks_by(&self, color: Color) -> Bitboard {
+        let occupancy = !self[Piece::Empty];
+        let queen = self[Piece::queen(color)];
+        self.pawn_attacks(self[Piece::pawn(color)], color)
+            | self.knight_attacks(self[Piece::knight(color)])
+            | self.king_attacks(self[Piece::king(color)])
+            | slider_attacks(self[Piece::bishop(color)] | queen, occupancy, &BISHOP_DIRS)
+            | slider_attacks(self[Piece::rook(color)] | queen, occupancy, &ROOK_DIRS)
+    }
+
+    /// Returns the squares attacked by the piece on the given square, or an empty [Bitboard] if
+    /// there is no piece on it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let board = Board::default();
+    /// assert_eq!(board.attacks_from("g1".parse()?).count_squares(), 3);
+    /// assert!(board.attacks_from("e4".parse()?).is_empty());
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn attacks_from(&self, square: Square) -> Bitboard {
+        match self[square] {
+            Piece::Filled(kind, color) => {
+                self.piece_attacks(kind, color, square.into(), !self[Piece::Empty])
+            }
+            Piece::Empty => EMPTY,
+        }
+    }
+
+    /// Squares attacked by pieces of the given kind and [Color] standing on `initial`, with only
+    /// the pieces in `occupancy` blocking sliders
+    #[inline]
+    pub(super) fn piece_attacks(
+        &self,
+        kind: PieceKind,
+        color: Color,
+        initial: Bitboard,
+        occupancy: Bitboard,
+    ) -> Bitboard {
+        match kind {
+            PieceKind::Pawn => self.pawn_attacks(initial, color),
+            PieceKind::Knight => self.knight_attacks(initial),
+            PieceKind::Bishop => slider_attacks(initial, occupancy, &BISHOP_DIRS),
+            PieceKind::Rook => slider_attacks(initial, occupancy, &ROOK_DIRS),
+            PieceKind::Queen => slider_attacks(initial, occupancy, &ALL_DIRS),
+            PieceKind::King => self.king_attacks(initial),
+        }
+    }
+
+    /// Returns how many pieces of the given [Color] attack each square, indexed like the board
+    /// from h8 to a1.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color, Square};
+    /// let board = Board::default();
+    /// let counts = board.attacker_counts(Color::White);
+    ///
+    /// // f3 is attacked by the e2 and g2 pawns and the g1 knight
+    /// assert_eq!(counts["f3".parse::<Square>()?.index() as usize], 3);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn attacker_counts(&self, color: Color) -> [u8; 64] {
+        let mut counts = [0; 64];
+        for origin in self[color] {
+            for square in self.attacks_from(origin) {
+                counts[square] += 1;
+            }
+        }
+        counts
+    }
+
     #[inline]
     pub(super) fn update_pins_and_checks(&mut self) {
         let mut p = EMPTY;
         let mut c = EMPTY;
         let color = self.color_to_move;
 
-        let initial: Bitboard = self.king(color).into();
+        let king = match self.king(color) {
+            Some(king) if self.variant.has_check() => king,
+            _ => {
+                self.pins = EMPTY;
+                self.check = Check::None;
+                return;
+            }
+        };
+
+        let initial: Bitboard = king.into();
         let def = self[color] ^ initial;
         let free = self[Piece::Empty];
         let queen = self[Piece::queen(!color)];
@@ -152,6 +287,16 @@ impl Board {
     }
 }
 
+/// Squares reached by sliding from `initial` in the given directions, up to and including the
+/// first occupied square
+#[inline]
+fn slider_attacks(initial: Bitboard, occupancy: Bitboard, dirs: &[Dir]) -> Bitboard {
+    dirs.iter().fold(EMPTY, |o, d| {
+        let filter = d.filter();
+        o | moves(initial, !occupancy & filter, occupancy & filter, d.offset())
+    })
+}
+
 #[inline]
 fn moves(initial: Bitboard, free: Bitboard, cap: Bitboard, dir: i32) -> Bitboard {
     let mut output = EMPTY;
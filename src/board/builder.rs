@@ -1,11 +1,17 @@
-use std::{ops, str::FromStr};
+use std::{ops, sync::Arc};
 
-use regex::Regex;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::{Board, BoardIter};
-use crate::{BoardError, Castle, Check, Color, Dir, ErrorKind, Piece, PieceKind, Square};
+use crate::{
+    BoardError, Castle, Check, Color, Dir, Piece, PositionError, Square, Variant, ZobristKeys,
+};
+
+mod fen;
+mod validate;
+pub use fen::{FenNormalization, FenOptions};
+pub use validate::{Severity, ValidationIssue};
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -15,8 +21,17 @@ pub struct BoardBuilder {
     color_to_move: Color,
     castle: [Castle; 2],
     ep_target: Option<Square>,
+    variant: Variant,
     halfmove: u32,
     fullmove: u32,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default = "super::hash::default_keys",
+            with = "super::hash::serde_keys"
+        )
+    )]
+    keys: Arc<ZobristKeys>,
 }
 
 impl IntoIterator for &BoardBuilder {
@@ -53,8 +68,10 @@ impl Default for BoardBuilder {
             castle: [Castle::Both; 2],
             color_to_move: Color::White,
             ep_target: None,
+            variant: Variant::Standard,
             halfmove: 0,
             fullmove: 1,
+            keys: ZobristKeys::default_keys().clone(),
         }
     }
 }
@@ -82,11 +99,31 @@ impl BoardBuilder {
             color_to_move: Color::White,
             castle: [Castle::None; 2],
             ep_target: None,
+            variant: Variant::Standard,
             halfmove: 0,
             fullmove: 1,
+            keys: ZobristKeys::default_keys().clone(),
         }
     }
 
+    /// Returns a [BoardBuilder] containing the starting position of the given [Variant]
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{BoardBuilder, Variant};
+    /// let board = BoardBuilder::for_variant(Variant::Antichess).build()?;
+    ///
+    /// assert_eq!(board.variant(), Variant::Antichess);
+    /// assert_eq!(board.to_fen(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1 antichess");
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn for_variant(variant: Variant) -> Self {
+        let mut builder =
+            Self::from_fen(variant.starting_fen()).expect("Starting positions are valid FEN");
+        builder.variant(variant);
+        builder
+    }
+
     /// Creates a new [BoardBuilder] from a string in Forsynth-Edwards Notation (FEN). Returns a
     /// [BoardError] if the string is improperly formatted.
     ///
@@ -114,7 +151,8 @@ impl BoardBuilder {
     ///
     /// assert!(builder.is_err());
     ///
-    /// // The half and full move counts are optional
+    /// // The half and full move counts are optional. See [BoardBuilder::from_fen_with_options]
+    /// // for reading less tidy FEN
     /// let fen_with_no_move_count = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -";
     /// let builder = BoardBuilder::from_fen(fen_with_no_move_count);
     ///
@@ -123,78 +161,7 @@ impl BoardBuilder {
     /// # Ok::<(), chb_chess::BoardError>(());
     /// ```
     pub fn from_fen(fen: &str) -> Result<Self, BoardError> {
-        let mut builder = Self::new();
-        let short_err = || BoardError::new(ErrorKind::InvalidInput, "Missing sections of FEN");
-        let mut sections = fen.split(' ');
-
-        let b = sections.next().ok_or_else(short_err)?;
-
-        let mut row_count = 0;
-        let mut pos_count = 0;
-        for (y, row) in b.split('/').enumerate() {
-            let mut offset: usize = 0;
-            for (x, symbol) in row.chars().rev().enumerate() {
-                if symbol.is_numeric() {
-                    let o = symbol.to_string().parse::<usize>()?;
-                    pos_count += o;
-                    offset += o - 1;
-                    continue;
-                }
-                let p: Piece = symbol.try_into()?;
-                let square: Square = ((y << 3) + x + offset).try_into()?;
-                builder.pieces[square] = p;
-                pos_count += 1;
-            }
-            row_count += 1;
-        }
-        if row_count != 8 || pos_count != 64 {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Invalid Position Input. Row or Position count did not match expected",
-            ));
-        }
-
-        builder.color_to_move = sections.next().ok_or_else(short_err)?.parse()?;
-
-        let castling = sections.next().ok_or_else(short_err)?;
-        let c_re =
-            Regex::new(r"^(?:K?Q?k?q?|-)$").expect("Invalid Regex used to check castling rights");
-        if !c_re.is_match(castling) {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Castling rights are invalid",
-            ));
-        }
-        for c in castling.chars() {
-            let p: Piece = c.try_into()?;
-            match p {
-                Piece::Filled(PieceKind::King, color) => {
-                    builder.castle[color] = builder.castle[color].with_king_side(true)
-                }
-                Piece::Filled(PieceKind::Queen, color) => {
-                    builder.castle[color] = builder.castle[color].with_queen_side(true)
-                }
-                _ => (),
-            }
-        }
-
-        // EP Target
-        if let Ok(p) = Square::from_str(sections.next().ok_or_else(short_err)?) {
-            builder.ep_target = Some(p);
-            // Check rank and if there is a pawn in capture position right above it based on color to move
-        }
-
-        // Move counts
-        builder.halfmove = match sections.next() {
-            Some(hm) => hm.parse()?,
-            None => 0,
-        };
-        builder.fullmove = match sections.next() {
-            Some(fm) => fm.parse()?,
-            None => 1,
-        };
-
-        Ok(builder)
+        Self::from_fen_with_options(fen, &FenOptions::default()).map(|(builder, _)| builder)
     }
 
     /// Puts the given [Piece] at the [Square]. Removes the [Piece] that was previously there.
@@ -244,12 +211,25 @@ impl BoardBuilder {
         self
     }
 
+    /// Sets the [Variant] whose rules the board will be played under
+    pub fn variant(&mut self, variant: Variant) -> &mut Self {
+        self.variant = variant;
+        self
+    }
+
+    /// Sets the [ZobristKeys] the board will be hashed with
+    pub fn zobrist_keys(&mut self, keys: Arc<ZobristKeys>) -> &mut Self {
+        self.keys = keys;
+        self
+    }
+
     /// Validates everything necessary to ensure that the board can generate things like attacks,
     /// pins, checks. If `Ok`, the board is able to generate and make at least one move.
     ///
     /// Validates:
-    /// - Both kings exist
-    /// - Castling rights are only given if the king is at the starting position
+    /// - Each side has the number of kings the variant requires
+    /// - Castling rights are only given if the variant allows castling and the king is at the
+    ///   starting position
     /// - The en passant target square is in an appropriate location
     ///
     /// Does not validate:
@@ -257,49 +237,23 @@ impl BoardBuilder {
     ///   behavior/panics after one move if the king is captured.
     /// - The first team to move has legal moves. Won't lead to undefined behavior, but why set
     ///   it up.
+    ///
+    /// [BoardBuilder::validate] reports every problem instead of the first.
     fn partial_validate(&self) -> Result<(), BoardError> {
-        // Validate that
-        // both kings exist
-        let king_counts: (u32, u32) = self.pieces.into_iter().fold((0, 0), |(mut w, mut b), p| {
-            if let Piece::Filled(kind, color) = p {
-                if kind == PieceKind::King {
-                    match color {
-                        Color::White => w += 1,
-                        Color::Black => b += 1,
-                    }
-                }
-            }
-            (w, b)
-        });
-        if (1, 1) != king_counts {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Board must contain one white and one black king",
-            ));
-        }
-        // castling follows the rules
-        let w_king = self
-            .pieces
-            .into_iter()
-            .position(|p| p == Piece::king(Color::White))
-            .expect("King is guaranteed to be on the board");
-        if self.castle[Color::White] != Castle::None && w_king != 59 {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "White king may not castle if it is not at e1",
-            ));
+        match self.rule_errors().into_iter().next() {
+            Some(err) => Err(err.into()),
+            None => Ok(()),
         }
-        let b_king = self
-            .pieces
-            .into_iter()
-            .position(|p| p == Piece::king(Color::Black))
-            .expect("King is guaranteed to be on the board");
-        if self.castle[Color::Black] != Castle::None && b_king != 3 {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Black king may not castle if it is not at e8",
-            ));
+    }
+
+    /// Every rule checked by [BoardBuilder::partial_validate] that the builder breaks, in the
+    /// order they are checked
+    fn rule_errors(&self) -> Vec<PositionError> {
+        let mut errors = Vec::new();
+        if !self.variant.has_castling() && self.castle != [Castle::None; 2] {
+            errors.push(PositionError::CastlingNotAllowed);
         }
+        self.validate_kings(&mut errors);
         // ep_target is possible
         if let Some(target) = self.ep_target {
             let (allowed_rank, pawn_dir) = match self.color_to_move {
@@ -312,14 +266,57 @@ impl BoardBuilder {
                     .expect("on allowed rank so it can add North/South")]
                     != Piece::pawn(!self.color_to_move)
             {
-                return Err(BoardError::new(
-                    ErrorKind::InvalidInput,
-                    "Illegal En Passant target",
-                ));
+                errors.push(PositionError::EnPassantTarget(target));
             }
         }
+        errors
+    }
 
-        Ok(())
+    /// Validates that each side has the number of kings its variant requires and that castling
+    /// rights are only given if the king is at the starting position
+    fn validate_kings(&self, errors: &mut Vec<PositionError>) {
+        for color in [Color::White, Color::Black] {
+            let kings: Vec<usize> = self
+                .pieces
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, p)| (p == Piece::king(color)).then_some(i))
+                .collect();
+            if matches!(self.variant.king_count(color), Some(n) if n as usize != kings.len()) {
+                errors.push(PositionError::KingCount(color));
+            }
+            // castling follows the rules
+            let start = match color {
+                Color::White => 59,
+                Color::Black => 3,
+            };
+            if self.castle[color] != Castle::None && kings != [start] {
+                errors.push(PositionError::CastlingKingMoved(color));
+            }
+        }
+    }
+
+    /// Places the pieces on a new board with the side not to move to move, so that whether it is
+    /// in check can be read from the board
+    fn place(&self) -> Board {
+        let mut board = Board::empty();
+        board.keys = self.keys.clone();
+        board.variant = self.variant;
+        board.halfmove = self.halfmove;
+        board.fullmove = self.fullmove;
+
+        board.modify(|m| {
+            m.set_ep_target(self.ep_target);
+            m.set_castle(Color::White, self.castle[Color::White]);
+            m.set_castle(Color::Black, self.castle[Color::Black]);
+            if m.board.color_to_move() == self.color_to_move {
+                m.toggle_color_to_move();
+            }
+            for (sq, p) in self.pieces.iter().enumerate() {
+                m.put(*p, sq.try_into().expect("will be valid square"));
+            }
+        });
+        board
     }
 
     /// Builds a board using the current state of the BoardBuilder. If the current state is not
@@ -342,37 +339,17 @@ impl BoardBuilder {
     pub fn build(&self) -> Result<Board, BoardError> {
         self.partial_validate()?;
 
-        let mut board = Board::empty();
-        // color to move is opposite of what it should be so we can check if
-        // the current opposing king is in check. Board is invalid if it is
-        board.halfmove = self.halfmove;
-        board.fullmove = self.fullmove;
-
-        board.modify(|m| {
-            m.set_ep_target(self.ep_target);
-            m.set_castle(Color::White, self.castle[Color::White]);
-            m.set_castle(Color::Black, self.castle[Color::Black]);
-            if m.board.color_to_move() == self.color_to_move {
-                m.toggle_color_to_move();
-            }
-            for (sq, p) in self.pieces.iter().enumerate() {
-                m.put(*p, sq.try_into().expect("will be valid square"));
-            }
-        });
+        let mut board = self.place();
         if board.check != Check::None {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "First color to move may not be able to capture the opposing king",
-            ));
+            return Err(PositionError::OpponentInCheck.into());
         }
         // Switching color to move and updating attacks, pins, and checks
-        board.modify(|m| (m.toggle_color_to_move()));
+        board.modify(|m| m.toggle_color_to_move());
+
+        self.variant.validate(&board)?;
 
         if board.legal_moves().is_empty() {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "First color to move must have available moves",
-            ));
+            return Err(PositionError::NoLegalMoves.into());
         }
         Ok(board)
     }
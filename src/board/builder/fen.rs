@@ -0,0 +1,520 @@
+use std::fmt;
+
+use super::BoardBuilder;
+use crate::{BoardError, Castle, Color, FenError, FenField, Piece, Square, Variant};
+
+/// Which departures from canonical FEN [BoardBuilder::from_fen_with_options] accepts. Anything
+/// accepted that wasn't canonical is reported as a [FenNormalization].
+///
+/// [FenOptions::default] is what [BoardBuilder::from_fen] uses, and accepts everything it did
+/// before options could be given: split runs of empty squares, missing or sloppy counters, an
+/// empty castling field, an en passant field that isn't a square, and text after the counters.
+/// [FenOptions::strict] accepts nothing but canonical FEN and [FenOptions::lenient] accepts
+/// everything below.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct FenOptions {
+    /// The halfmove and fullmove counters may be left off, as in the first four fields of an
+    /// EPD record. They default to 0 and 1.
+    pub allow_missing_counters: bool,
+    /// A run of empty squares may be written as several digits, like `44` for `8`
+    pub allow_split_empty_squares: bool,
+    /// Fields may be separated by any amount of whitespace, with whitespace around the FEN
+    pub allow_extra_whitespace: bool,
+    /// Counters may be `-` for their default, have leading zeros, or give a fullmove of 0
+    pub allow_sloppy_counters: bool,
+    /// Castling rights may be in any order and repeated, like `kqQK`
+    pub allow_any_castling_order: bool,
+    /// Castling rights may be given with the file of the rook, as in X-FEN and Shredder-FEN,
+    /// like `HAha` for `KQkq`. Only rooks in the corners are supported.
+    pub allow_file_castling: bool,
+    /// The castling field may be empty, for no castling rights
+    pub allow_empty_castling: bool,
+    /// An en passant field that isn't `-` or a square is ignored
+    pub allow_invalid_ep_target: bool,
+    /// Anything after the fullmove counter is ignored
+    pub allow_trailing_text: bool,
+}
+
+impl Default for FenOptions {
+    fn default() -> Self {
+        Self {
+            allow_missing_counters: true,
+            allow_split_empty_squares: true,
+            allow_sloppy_counters: true,
+            allow_empty_castling: true,
+            allow_invalid_ep_target: true,
+            allow_trailing_text: true,
+            ..Self::strict()
+        }
+    }
+}
+
+impl FenOptions {
+    /// Only accepts canonical FEN with all six fields
+    pub fn strict() -> Self {
+        Self {
+            allow_missing_counters: false,
+            allow_split_empty_squares: false,
+            allow_extra_whitespace: false,
+            allow_sloppy_counters: false,
+            allow_any_castling_order: false,
+            allow_file_castling: false,
+            allow_empty_castling: false,
+            allow_invalid_ep_target: false,
+            allow_trailing_text: false,
+        }
+    }
+
+    /// Accepts every departure from canonical FEN that can be read unambiguously
+    pub fn lenient() -> Self {
+        Self {
+            allow_missing_counters: true,
+            allow_split_empty_squares: true,
+            allow_extra_whitespace: true,
+            allow_sloppy_counters: true,
+            allow_any_castling_order: true,
+            allow_file_castling: true,
+            allow_empty_castling: true,
+            allow_invalid_ep_target: true,
+            allow_trailing_text: true,
+        }
+    }
+}
+
+/// Something [BoardBuilder::from_fen_with_options] read from a FEN that wasn't canonical, and
+/// how it was read
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum FenNormalization {
+    /// Empty squares in the row with the given index, starting from the eighth rank, were
+    /// written with several digits
+    SplitEmptySquares(usize),
+    /// Whitespace other than single spaces between the fields was ignored
+    Whitespace,
+    /// The counter was missing and set to its default
+    MissingCounter(FenField),
+    /// The counter was written as `text` and read as `value`
+    Counter {
+        field: FenField,
+        text: String,
+        value: u32,
+    },
+    /// The castling rights were out of order or repeated, and were read as `castling`
+    CastlingOrder { text: String, castling: String },
+    /// The castling rights were given by rook files, and were read as `castling`
+    FileCastling { text: String, castling: String },
+    /// The castling field was empty, and was read as no castling rights
+    EmptyCastling,
+    /// The en passant field wasn't a square, and was ignored
+    InvalidEpTarget(String),
+    /// The text after the counters was ignored
+    TrailingText(String),
+}
+
+impl fmt::Display for FenNormalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenNormalization::SplitEmptySquares(row) => {
+                write!(f, "Merged the empty squares of row {}", row + 1)
+            }
+            FenNormalization::Whitespace => write!(f, "Ignored extra whitespace"),
+            FenNormalization::MissingCounter(field) => {
+                write!(f, "Used the default for the missing {field}")
+            }
+            FenNormalization::Counter { field, text, value } => {
+                write!(f, "Read the {field} `{text}` as {value}")
+            }
+            FenNormalization::CastlingOrder { text, castling }
+            | FenNormalization::FileCastling { text, castling } => {
+                write!(f, "Read the castling rights `{text}` as `{castling}`")
+            }
+            FenNormalization::EmptyCastling => write!(f, "Read the empty castling rights as `-`"),
+            FenNormalization::InvalidEpTarget(text) => {
+                write!(f, "Ignored the en passant target `{text}`")
+            }
+            FenNormalization::TrailingText(text) => write!(f, "Ignored `{text}` after the FEN"),
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Creates a new [BoardBuilder] from a string in Forsynth-Edwards Notation (FEN), accepting
+    /// the departures from canonical FEN allowed by `options`. Returns the builder along with
+    /// everything that had to be normalized to read it, in the order of the fields.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{BoardBuilder, FenField, FenNormalization, FenOptions};
+    /// let sloppy = " rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR  w HAha - ";
+    /// assert!(BoardBuilder::from_fen_with_options(sloppy, &FenOptions::strict()).is_err());
+    ///
+    /// let (builder, report) = BoardBuilder::from_fen_with_options(sloppy, &FenOptions::lenient())?;
+    /// assert_eq!(
+    ///     report,
+    ///     [
+    ///         FenNormalization::Whitespace,
+    ///         FenNormalization::FileCastling { text: "HAha".to_owned(), castling: "KQkq".to_owned() },
+    ///         FenNormalization::MissingCounter(FenField::Halfmove),
+    ///         FenNormalization::MissingCounter(FenField::Fullmove),
+    ///     ]
+    /// );
+    /// assert_eq!(builder.build()?, Default::default());
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn from_fen_with_options(
+        fen: &str,
+        options: &FenOptions,
+    ) -> Result<(Self, Vec<FenNormalization>), BoardError> {
+        let mut builder = Self::new();
+        let mut report = Vec::new();
+
+        let fields: Vec<&str> = if options.allow_extra_whitespace {
+            let fields: Vec<&str> = fen.split_whitespace().collect();
+            if fields.join(" ") != fen {
+                report.push(FenNormalization::Whitespace);
+            }
+            fields
+        } else {
+            fen.split(' ').collect()
+        };
+        let mut sections = fields.into_iter();
+        let mut next_section = |field| sections.next().ok_or_else(|| FenError::new(field, ""));
+
+        let placement = next_section(FenField::Placement)?;
+        builder.pieces = parse_placement(placement, options, &mut report)?;
+
+        let color = next_section(FenField::ColorToMove)?;
+        builder.color_to_move = color
+            .parse()
+            .map_err(|err| FenError::new(FenField::ColorToMove, color).with_source(err))?;
+
+        let castling = next_section(FenField::Castling)?;
+        builder.castle = parse_castling(castling, options, &mut report)?;
+
+        let ep_target = next_section(FenField::EnPassant)?;
+        if ep_target != "-" {
+            match ep_target.parse() {
+                Ok(square) => builder.ep_target = Some(square),
+                Err(_) if options.allow_invalid_ep_target => {
+                    report.push(FenNormalization::InvalidEpTarget(ep_target.to_owned()));
+                }
+                Err(err) => {
+                    return Err(FenError::new(FenField::EnPassant, ep_target)
+                        .with_source(err)
+                        .into())
+                }
+            }
+        }
+
+        builder.halfmove =
+            parse_counter(sections.next(), FenField::Halfmove, options, &mut report)?;
+        builder.fullmove =
+            parse_counter(sections.next(), FenField::Fullmove, options, &mut report)?;
+
+        // A variant other than Standard is named after the counters, and anything after it is
+        // not FEN
+        let rest: Vec<&str> = sections.collect();
+        let (variant, used) = Variant::read_fen(&rest);
+        builder.variant = variant;
+        let rest = &rest[used..];
+        if !rest.is_empty() {
+            let rest = rest.join(" ");
+            if !options.allow_trailing_text {
+                return Err(FenError::new(FenField::Fullmove, &rest).into());
+            }
+            report.push(FenNormalization::TrailingText(rest));
+        }
+        Ok((builder, report))
+    }
+}
+
+fn parse_placement(
+    placement: &str,
+    options: &FenOptions,
+    report: &mut Vec<FenNormalization>,
+) -> Result<[Piece; 64], BoardError> {
+    let placement_err = || FenError::new(FenField::Placement, placement);
+    let mut pieces = [Piece::Empty; 64];
+
+    let mut row_count = 0;
+    for (y, row) in placement.split('/').enumerate() {
+        let mut squares = 0;
+        let mut offset: usize = 0;
+        let mut after_digit = false;
+        let mut split = false;
+        for (x, symbol) in row.chars().rev().enumerate() {
+            if let Some(o) = symbol.to_digit(10) {
+                if o == 0 || o > 8 {
+                    return Err(placement_err().into());
+                }
+                split |= after_digit;
+                after_digit = true;
+                squares += o as usize;
+                offset += o as usize - 1;
+                continue;
+            }
+            after_digit = false;
+            let p: Piece = symbol
+                .try_into()
+                .map_err(|err| placement_err().with_source(err))?;
+            let square: Square = ((y << 3) + x + offset)
+                .try_into()
+                .map_err(|_| placement_err())?;
+            pieces[square] = p;
+            squares += 1;
+        }
+        // Every rank covers exactly 8 squares, so none spill into the next
+        if squares != 8 {
+            return Err(placement_err().into());
+        }
+        if split {
+            if !options.allow_split_empty_squares {
+                return Err(placement_err().into());
+            }
+            report.push(FenNormalization::SplitEmptySquares(y));
+        }
+        row_count += 1;
+    }
+    if row_count != 8 {
+        return Err(placement_err().into());
+    }
+    Ok(pieces)
+}
+
+fn parse_castling(
+    text: &str,
+    options: &FenOptions,
+    report: &mut Vec<FenNormalization>,
+) -> Result<[Castle; 2], BoardError> {
+    let castling_err = || FenError::new(FenField::Castling, text);
+    let mut castle = [Castle::None; 2];
+    if text == "-" {
+        return Ok(castle);
+    }
+    if text.is_empty() {
+        if !options.allow_empty_castling {
+            return Err(castling_err().into());
+        }
+        report.push(FenNormalization::EmptyCastling);
+        return Ok(castle);
+    }
+
+    let mut by_file = false;
+    for c in text.chars() {
+        let color = if c.is_ascii_uppercase() {
+            Color::White
+        } else {
+            Color::Black
+        };
+        // Only rooks in the corners are supported, so their files are the only ones allowed
+        let king_side = match c.to_ascii_lowercase() {
+            'k' => true,
+            'q' => false,
+            'h' | 'a' if options.allow_file_castling => {
+                by_file = true;
+                c.eq_ignore_ascii_case(&'h')
+            }
+            _ => return Err(castling_err().into()),
+        };
+        castle[color] = if king_side {
+            castle[color].with_king_side(true)
+        } else {
+            castle[color].with_queen_side(true)
+        };
+    }
+
+    let castling = castling_string(castle);
+    if by_file {
+        report.push(FenNormalization::FileCastling {
+            text: text.to_owned(),
+            castling,
+        });
+    } else if castling != text {
+        if !options.allow_any_castling_order {
+            return Err(castling_err().into());
+        }
+        report.push(FenNormalization::CastlingOrder {
+            text: text.to_owned(),
+            castling,
+        });
+    }
+    Ok(castle)
+}
+
+/// The castling field of a FEN with the given rights
+fn castling_string(castle: [Castle; 2]) -> String {
+    if castle == [Castle::None; 2] {
+        return "-".to_owned();
+    }
+    let white = castle[Color::White].to_string().to_ascii_uppercase();
+    let black = castle[Color::Black].to_string();
+    [white, black].concat().replace('-', "")
+}
+
+fn parse_counter(
+    text: Option<&str>,
+    field: FenField,
+    options: &FenOptions,
+    report: &mut Vec<FenNormalization>,
+) -> Result<u32, BoardError> {
+    let default = match field {
+        FenField::Fullmove => 1,
+        _ => 0,
+    };
+    let Some(text) = text else {
+        if !options.allow_missing_counters {
+            return Err(FenError::new(field, "").into());
+        }
+        report.push(FenNormalization::MissingCounter(field));
+        return Ok(default);
+    };
+
+    let value = match text {
+        "-" => default,
+        _ => text
+            .parse::<u32>()
+            .map_err(|_| FenError::new(field, text))?
+            .max(default),
+    };
+    if value.to_string() != text {
+        if !options.allow_sloppy_counters {
+            return Err(FenError::new(field, text).into());
+        }
+        report.push(FenNormalization::Counter {
+            field,
+            text: text.to_owned(),
+            value,
+        });
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, BoardBuilder, FenField, FenNormalization, FenOptions};
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn parse(fen: &str, options: FenOptions) -> Option<Vec<FenNormalization>> {
+        BoardBuilder::from_fen_with_options(fen, &options)
+            .ok()
+            .map(|(_, report)| report)
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(parse(START, FenOptions::strict()), Some(vec![]));
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+            "rnbqkbnr/pppppppp/44/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -  0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 ",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w QKkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w  - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - - 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 01",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq x3 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 bm e4;",
+            "rnbqkbnr/pppppppp/9/7/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        ] {
+            assert_eq!(parse(fen, FenOptions::strict()), None, "{fen}");
+        }
+        // A rank that doesn't cover 8 squares is never accepted
+        for fen in [
+            "rnbqkbnr/pppppppp/9/7/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        ] {
+            assert_eq!(parse(fen, FenOptions::lenient()), None, "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_lenient() {
+        let report = parse(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR\tw qkKQk - - 0",
+            FenOptions::lenient(),
+        );
+        assert_eq!(
+            report,
+            Some(vec![
+                FenNormalization::Whitespace,
+                FenNormalization::CastlingOrder {
+                    text: "qkKQk".to_owned(),
+                    castling: "KQkq".to_owned()
+                },
+                FenNormalization::Counter {
+                    field: FenField::Halfmove,
+                    text: "-".to_owned(),
+                    value: 0
+                },
+                FenNormalization::Counter {
+                    field: FenField::Fullmove,
+                    text: "0".to_owned(),
+                    value: 1
+                },
+            ])
+        );
+
+        let (builder, report) = BoardBuilder::from_fen_with_options(
+            "4k2r/8/8/8/8/8/8/R3K111 w Ah - 12",
+            &FenOptions::lenient(),
+        )
+        .unwrap();
+        assert_eq!(
+            builder.build().unwrap().to_fen(),
+            "4k2r/8/8/8/8/8/8/R3K3 w Qk - 12 1"
+        );
+        assert_eq!(report.len(), 3);
+        assert_eq!(report[0], FenNormalization::SplitEmptySquares(7));
+
+        // Rooks that don't start in a corner aren't supported
+        assert_eq!(
+            parse("1r2k3/8/8/8/8/8/8/4K3 b b - 0 1", FenOptions::lenient()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        // Everything from_fen accepted before it took options is still accepted
+        for (fen, normalization) in [
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0",
+                FenNormalization::Counter {
+                    field: FenField::Fullmove,
+                    text: "0".to_owned(),
+                    value: 1,
+                },
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 01",
+                FenNormalization::Counter {
+                    field: FenField::Fullmove,
+                    text: "01".to_owned(),
+                    value: 1,
+                },
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq x3 0 1",
+                FenNormalization::InvalidEpTarget("x3".to_owned()),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K3 w  - 0 1",
+                FenNormalization::EmptyCastling,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 bm e4;",
+                FenNormalization::TrailingText("bm e4;".to_owned()),
+            ),
+        ] {
+            assert_eq!(
+                parse(fen, FenOptions::default()),
+                Some(vec![normalization]),
+                "{fen}"
+            );
+            assert!(Board::from_fen(fen).is_ok(), "{fen}");
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::fmt;
+
+use super::BoardBuilder;
+use crate::{
+    Bitboard, Board, BoardError, Check, Color, Dir, Move, Piece, PieceKind, PositionError, Ray,
+    Square,
+};
+
+/// How serious a [ValidationIssue] is
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Severity {
+    /// The position breaks the rules, so [BoardBuilder::build] refuses it
+    Illegal,
+    /// The position can be built and played from, but could never come up in a game
+    Unreachable,
+}
+
+/// A problem with the position in a [BoardBuilder], as reported by [BoardBuilder::validate]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+    /// A rule that [BoardBuilder::build] enforces
+    Rule(PositionError),
+    /// A pawn stands on its first rank or its promotion rank
+    PawnOnBackRank(Square),
+    /// The side has more pawns than it starts with
+    TooManyPawns(Color),
+    /// The side has more pieces than its starting ones and promotions of its missing pawns can
+    /// account for
+    TooManyPieces(Color),
+    /// More than two pieces give check at once
+    TooManyCheckers(Bitboard),
+    /// Two pieces give check at once, but neither is a slider that the other could have
+    /// uncovered by moving off the line between it and the king
+    ImpossibleDoubleCheck(Bitboard),
+}
+
+impl ValidationIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssue::Rule(_) => Severity::Illegal,
+            _ => Severity::Unreachable,
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::Rule(err) => err.fmt(f),
+            ValidationIssue::PawnOnBackRank(square) => {
+                write!(f, "The pawn on {square} can't be on its back rank")
+            }
+            ValidationIssue::TooManyPawns(color) => {
+                write!(f, "{color:?} has more pawns than it starts with")
+            }
+            ValidationIssue::TooManyPieces(color) => {
+                write!(f, "{color:?} has more pieces than promotions can explain")
+            }
+            ValidationIssue::TooManyCheckers(checkers) => {
+                write!(f, "{} pieces give check at once", checkers.count_squares())
+            }
+            ValidationIssue::ImpossibleDoubleCheck(_) => {
+                write!(f, "Neither checking piece could have uncovered the other")
+            }
+        }
+    }
+}
+
+impl BoardBuilder {
+    /// Reports every problem with the position at once, each with a [Severity]. Any
+    /// [Severity::Illegal] issue means [BoardBuilder::build] will fail, while
+    /// [Severity::Unreachable] issues are positions that can be played from but could not come
+    /// up in a game: pawns on the first or last rank, more pawns or pieces than a side can have,
+    /// and checks that no single move could give.
+    ///
+    /// Checks can only be looked at once the kings, castling rights and en passant target are
+    /// valid, so they are not reported until then.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{BoardBuilder, Color, PositionError, Severity, ValidationIssue};
+    /// // White is missing its king and has a pawn on the eighth rank
+    /// let builder = BoardBuilder::from_fen("P3k3/8/8/8/8/8/8/8 w - - 0 1")?;
+    /// let issues = builder.validate();
+    ///
+    /// assert_eq!(
+    ///     issues,
+    ///     [
+    ///         ValidationIssue::Rule(PositionError::KingCount(Color::White)),
+    ///         ValidationIssue::PawnOnBackRank("a8".parse()?),
+    ///     ]
+    /// );
+    /// assert_eq!(issues[0].severity(), Severity::Illegal);
+    /// assert_eq!(issues[1].severity(), Severity::Unreachable);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues: Vec<ValidationIssue> = self
+            .rule_errors()
+            .into_iter()
+            .map(ValidationIssue::Rule)
+            .collect();
+        if issues.is_empty() {
+            self.validate_position(&mut issues);
+        }
+        self.validate_pawns(&mut issues);
+        for color in [Color::White, Color::Black] {
+            self.validate_material(color, &mut issues);
+        }
+        issues
+    }
+
+    /// Sets up the board to check the rules that depend on attacks
+    fn validate_position(&self, issues: &mut Vec<ValidationIssue>) {
+        let mut board = self.place();
+        if board.check != Check::None {
+            issues.push(ValidationIssue::Rule(PositionError::OpponentInCheck));
+        }
+        board.modify(|m| m.toggle_color_to_move());
+        if let Err(BoardError::Position(err)) = self.variant.validate(&board) {
+            issues.push(ValidationIssue::Rule(err));
+        }
+        if board.legal_moves().is_empty() {
+            issues.push(ValidationIssue::Rule(PositionError::NoLegalMoves));
+        }
+
+        let color = board.color_to_move;
+        let Some(king) = board.king(color).filter(|_| self.variant.has_check()) else {
+            return;
+        };
+        let checkers = board.attackers_to(king, !board[Piece::Empty]) & board[!color];
+        match checkers.count_squares() {
+            0 | 1 => (),
+            2 if double_check_reachable(&board, checkers, king) => (),
+            2 => issues.push(ValidationIssue::ImpossibleDoubleCheck(checkers)),
+            _ => issues.push(ValidationIssue::TooManyCheckers(checkers)),
+        }
+    }
+
+    fn validate_pawns(&self, issues: &mut Vec<ValidationIssue>) {
+        for (index, piece) in self.pieces.iter().enumerate() {
+            let Piece::Filled(PieceKind::Pawn, color) = piece else {
+                continue;
+            };
+            let square = Square::try_from(index).expect("Every index of the board is a square");
+            let first_rank = match color {
+                Color::White => square.rank() == 7,
+                Color::Black => square.rank() == 0,
+            };
+            let allowed = first_rank && self.variant.allows_first_rank_pawns(*color);
+            if (square.rank() == 0 || square.rank() == 7) && !allowed {
+                issues.push(ValidationIssue::PawnOnBackRank(square));
+            }
+        }
+    }
+
+    fn validate_material(&self, color: Color, issues: &mut Vec<ValidationIssue>) {
+        // The material is compared with what the side starts with in this variant
+        let count = |kind| {
+            self.pieces
+                .iter()
+                .filter(|p| **p == Piece::Filled(kind, color))
+                .count() as u32
+        };
+        let start = |kind| self.variant.starting_count(Piece::Filled(kind, color));
+        let pawns = count(PieceKind::Pawn);
+        if pawns > start(PieceKind::Pawn) {
+            issues.push(ValidationIssue::TooManyPawns(color));
+        }
+        let promotions = start(PieceKind::Pawn).saturating_sub(pawns);
+        let extra: u32 = [
+            PieceKind::King,
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+        ]
+        .into_iter()
+        .map(|kind| count(kind).saturating_sub(start(kind)))
+        .sum();
+        if extra > promotions {
+            issues.push(ValidationIssue::TooManyPieces(color));
+        }
+    }
+}
+
+/// Whether either of the two checkers could have just moved off the line between the king and
+/// the other, uncovering its check, or an en passant capture uncovered both
+fn double_check_reachable(board: &Board, checkers: Bitboard, king: Square) -> bool {
+    let squares: Vec<Square> = checkers.into_iter().collect();
+    let [first, second] = squares[..] else {
+        return false;
+    };
+    uncovers(board, first, second, king)
+        || uncovers(board, second, first, king)
+        || en_passant_uncovers(board, first, second, king)
+}
+
+/// The squares between the king and a slider checking it, which are empty
+fn between(king: Square, slider: Square) -> Vec<Square> {
+    Ray::from(king, slider).map_or(Vec::new(), |line| {
+        line.into_iter().take_while(|sq| *sq != slider).collect()
+    })
+}
+
+/// Whether a pawn capturing en passant could have left one of its origin and the captured
+/// pawn's square on the line of each checker
+fn en_passant_uncovers(board: &Board, first: Square, second: Square, king: Square) -> bool {
+    let Some(color) = board[first].color() else {
+        return false;
+    };
+    let (back, ep_rank) = match color {
+        Color::White => (Dir::South, 2),
+        Color::Black => (Dir::North, 5),
+    };
+    let (first, second) = (between(king, first), between(king, second));
+    board[Piece::pawn(color)]
+        .into_iter()
+        .filter(|pawn| pawn.rank() == ep_rank)
+        .filter_map(|pawn| pawn.checked_add(back))
+        .any(|captured| {
+            [Dir::East, Dir::West]
+                .into_iter()
+                .filter_map(|dir| captured.checked_add(dir))
+                .any(|origin| {
+                    first.contains(&captured) && second.contains(&origin)
+                        || first.contains(&origin) && second.contains(&captured)
+                })
+        })
+}
+
+/// Whether the piece on `mover` could have moved there from a square between the king and the
+/// slider on `slider`, including as a pawn that promoted
+fn uncovers(board: &Board, slider: Square, mover: Square, king: Square) -> bool {
+    let Piece::Filled(kind, color) = board[mover] else {
+        return false;
+    };
+    let last_rank = match color {
+        Color::White => 0,
+        Color::Black => 7,
+    };
+    let promoted = mover.rank() == last_rank && board.variant.promotion_kinds().contains(&kind);
+
+    // A piece on the last rank may have been a pawn before it promoted
+    let moved = [Some(board[mover]), promoted.then_some(Piece::pawn(color))];
+    // Only a slider has squares between it and the king it checks
+    between(king, slider).into_iter().any(|origin| {
+        moved.into_iter().flatten().any(|moved| {
+            let mut before = board.clone();
+            before.modify(|m| {
+                m.clear(mover);
+                m.put(moved, origin);
+            });
+            let mv = Move {
+                origin,
+                dest: mover,
+                promotion: Piece::Empty,
+            };
+            // Nothing may stand in the way of the move
+            before.movement(mv).is_some_and(|(path, _)| {
+                path.is_none_or(|path| {
+                    path.into_iter()
+                        .take_while(|sq| *sq != mover)
+                        .all(|sq| before[sq] == Piece::Empty)
+                })
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoardBuilder, Color, PositionError, Severity, ValidationIssue, Variant};
+
+    fn issues(fen: &str) -> Vec<ValidationIssue> {
+        BoardBuilder::from_fen(fen).unwrap().validate()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            issues("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+            []
+        );
+
+        // Every rule is reported, not just the first
+        assert_eq!(
+            issues("4k3/8/8/8/8/8/8/4K1K1 w KQ e6 0 1"),
+            [
+                ValidationIssue::Rule(PositionError::KingCount(Color::White)),
+                ValidationIssue::Rule(PositionError::CastlingKingMoved(Color::White)),
+                ValidationIssue::Rule(PositionError::EnPassantTarget("e6".parse().unwrap())),
+            ]
+        );
+
+        // Black to move can capture the white king
+        assert_eq!(
+            issues("4k3/8/8/8/8/8/8/r3K3 b - - 0 1"),
+            [ValidationIssue::Rule(PositionError::OpponentInCheck)]
+        );
+
+        // Two knights can't give check together, and three pieces never can
+        let double = issues("4k3/8/3N1N2/8/8/8/8/4K3 b - - 0 1");
+        assert!(matches!(
+            double[..],
+            [ValidationIssue::ImpossibleDoubleCheck(_)]
+        ));
+        let triple = issues("4k3/8/3N1N2/8/8/8/8/4RK2 b - - 0 1");
+        assert!(matches!(triple[..], [ValidationIssue::TooManyCheckers(_)]));
+        // A rook uncovered by a knight is fine
+        assert_eq!(issues("4k3/8/3N4/8/8/8/8/4RK2 b - - 0 1"), []);
+        // Neither rook stood between the other and the king
+        let rooks = issues("R3k3/8/8/8/8/8/8/4RK2 b - - 0 1");
+        assert!(matches!(
+            rooks[..],
+            [ValidationIssue::ImpossibleDoubleCheck(_)]
+        ));
+        // A pawn on d7 uncovered the bishop by promoting to a rook, and one capturing en
+        // passant uncovered both the bishop and the rook
+        assert_eq!(issues("2R1k3/8/8/8/B7/8/8/4K3 b - - 0 1"), []);
+        assert_eq!(issues("B3R3/8/3P4/8/4k3/8/8/7K b - - 0 1"), []);
+
+        // Three queens need two promotions, but only one pawn is missing
+        let fen = "4k3/8/8/8/8/8/PPPPPPP1/QQQ1K3 w - - 0 1";
+        assert_eq!(issues(fen), [ValidationIssue::TooManyPieces(Color::White)]);
+        assert_eq!(issues(fen)[0].severity(), Severity::Unreachable);
+        assert!(BoardBuilder::from_fen(fen).unwrap().build().is_ok());
+
+        // The horde's pawns may stand on the first rank
+        let mut horde = BoardBuilder::from_fen(
+            "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1",
+        )
+        .unwrap();
+        horde.variant(Variant::Horde);
+        assert_eq!(horde.validate(), []);
+    }
+}
@@ -0,0 +1,135 @@
+use super::Board;
+use crate::{Bitboard, Color, Move, MoveError, Piece, PieceKind, Square, EMPTY};
+
+impl Board {
+    /// Returns the squares each piece of the side to move can legally move to, indexed by the
+    /// [Square] it starts on. Castling is a king move of two squares.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Square};
+    /// let board = Board::default();
+    /// let destinations = board.legal_destinations();
+    ///
+    /// let knight: Square = "g1".parse()?;
+    /// assert_eq!(destinations[knight].count_squares(), 2);
+    /// assert!(destinations[knight].contains("f3".parse()?));
+    /// assert!(destinations["e1".parse::<Square>()?].is_empty());
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn legal_destinations(&self) -> [Bitboard; 64] {
+        let mut destinations = [EMPTY; 64];
+        for mv in self.legal_moves() {
+            if !mv.is_drop() && !mv.is_null() {
+                destinations[mv.origin] |= mv.dest.into();
+            }
+        }
+        destinations
+    }
+
+    /// Returns true if moving the piece on `origin` to `dest` is a promotion, so a piece to
+    /// promote to has to be chosen
+    pub fn is_promotion(&self, origin: Square, dest: Square) -> bool {
+        let last_rank = match self.color_to_move {
+            Color::White => 0,
+            Color::Black => 7,
+        };
+        self[origin] == Piece::pawn(self.color_to_move) && dest.rank() == last_rank
+    }
+
+    /// Finds the legal move from `origin` to `dest`, as a GUI would get from clicking or
+    /// dragging between two squares. `promotion` is the piece chosen when the move
+    /// [is a promotion](Board::is_promotion), and is otherwise ignored. Moving the king onto
+    /// one of its own rooks castles with that rook.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [MoveError] saying why the move is illegal, as [Board::why_illegal] does, or
+    /// [MoveError::PromotionRequired] if the move promotes and `promotion` is `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, MoveError, PieceKind};
+    /// let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K2R w K - 0 1")?;
+    ///
+    /// let castle = board.move_from_squares("e1".parse()?, "h1".parse()?, None)?;
+    /// assert_eq!(castle, "e1g1".parse()?);
+    ///
+    /// let promotion = board.move_from_squares("b7".parse()?, "b8".parse()?, Some(PieceKind::Knight))?;
+    /// assert_eq!(promotion, "b7b8n".parse()?);
+    /// assert_eq!(
+    ///     board.move_from_squares("b7".parse()?, "b8".parse()?, None),
+    ///     Err(MoveError::PromotionRequired("b7b8".parse()?))
+    /// );
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn move_from_squares(
+        &self,
+        origin: Square,
+        dest: Square,
+        promotion: Option<PieceKind>,
+    ) -> Result<Move, MoveError> {
+        let color = self.color_to_move;
+        if self[origin] == Piece::king(color)
+            && self[dest] == Piece::rook(color)
+            && origin.rank() == dest.rank()
+        {
+            // The king castles two squares toward the rook
+            let index = match dest.index() < origin.index() {
+                true => origin.index() - 2,
+                false => origin.index() + 2,
+            };
+            let castle = Move {
+                origin,
+                dest: Square::try_from(index as usize).expect("The king is on its first rank"),
+                promotion: Piece::Empty,
+            };
+            return self.check_legal(castle).map(|_| castle);
+        }
+
+        let promotion = match promotion {
+            Some(kind) if self.is_promotion(origin, dest) => Piece::Filled(kind, color),
+            _ => Piece::Empty,
+        };
+        let mv = Move {
+            origin,
+            dest,
+            promotion,
+        };
+        self.check_legal(mv).map(|_| mv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, MoveError, PieceKind, Square};
+
+    fn sq(s: &str) -> Square {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_destinations() {
+        // The four promotions share one destination
+        let board = Board::from_fen("4k3/2P5/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(board.legal_destinations()[sq("c7")], sq("c8").into());
+        assert!(board.is_promotion(sq("c7"), sq("c8")));
+        assert!(!board.is_promotion(sq("e1"), sq("e2")));
+
+        // The pawn on c7 attacks d8, so the king can't castle queenside through it
+        let board = Board::from_fen("r3k2r/2P5/8/8/8/8/8/4K3 b kq - 0 1").unwrap();
+        let king = board.legal_destinations()[sq("e8")];
+        assert!(king.contains(sq("g8")) && !king.contains(sq("c8")));
+        assert_eq!(
+            board.move_from_squares(sq("e8"), sq("h8"), None),
+            Ok("e8g8".parse().unwrap())
+        );
+        assert_eq!(
+            board.move_from_squares(sq("e8"), sq("a8"), Some(PieceKind::Queen)),
+            Err(MoveError::CastlingThroughCheck(
+                "e8c8".parse().unwrap(),
+                sq("d8")
+            ))
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{Bitboard, Board, Check, Color, Dir, Piece, PieceKind, DARK_SQUARES, LIGHT_SQUARES};
+
+impl Board {
+    /// Returns true if neither side has the material to checkmate by any sequence of legal moves,
+    /// which under FIDE rules ends the game in a draw. That is king against king, king and a
+    /// single knight or bishop against king, or any number of bishops that all stand on squares of
+    /// the same color.
+    ///
+    /// Always false in variants other than Standard, which have their own ways of winning.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// assert!(Board::from_fen("8/8/4k3/8/8/2B5/4K3/8 w - - 0 1")?.is_insufficient_material());
+    /// // Bishops on opposite colors can still mate with help
+    /// assert!(!Board::from_fen("8/8/4k3/3b4/8/2B5/4K3/8 w - - 0 1")?.is_insufficient_material());
+    /// assert!(!Board::default().is_insufficient_material());
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn is_insufficient_material(&self) -> bool {
+        if !self.variant.has_material_draws() {
+            return false;
+        }
+        let heavy = [PieceKind::Pawn, PieceKind::Rook, PieceKind::Queen]
+            .into_iter()
+            .any(|kind| !self.pieces_of_kind(kind).is_empty());
+        if heavy {
+            return false;
+        }
+
+        let knights = self.pieces_of_kind(PieceKind::Knight);
+        let bishops = self.pieces_of_kind(PieceKind::Bishop);
+        if (knights | bishops).count_squares() <= 1 {
+            return true;
+        }
+        knights.is_empty()
+            && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & DARK_SQUARES).is_empty())
+    }
+
+    /// Returns true if the position is dead, meaning neither side can ever checkmate. Along with
+    /// [Board::is_insufficient_material], this recognizes positions with only kings and pawns
+    /// where every pawn is blocked by an enemy pawn, no pawn can capture, and neither king can
+    /// reach an enemy pawn.
+    ///
+    /// This is conservative, so a position it doesn't recognize may still be dead.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let board = Board::from_fen("8/4k3/8/1p1p1p1p/1P1P1P1P/8/4K3/8 w - - 0 1")?;
+    /// assert!(board.is_dead_position());
+    ///
+    /// // The white king can walk around to the black pawns on the a-file
+    /// let board = Board::from_fen("8/4k3/p7/P7/8/8/4K3/8 w - - 0 1")?;
+    /// assert!(!board.is_dead_position());
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn is_dead_position(&self) -> bool {
+        if self.is_insufficient_material() {
+            return true;
+        }
+        if !self.variant.has_material_draws()
+            || self.check != Check::None
+            || self.ep_target.is_some()
+        {
+            return false;
+        }
+        let pawns = self.pieces_of_kind(PieceKind::Pawn);
+        let kings = self.pieces_of_kind(PieceKind::King);
+        if pawns | kings != !self[Piece::Empty] {
+            return false;
+        }
+
+        let white_pawns = self[Piece::pawn(Color::White)];
+        let black_pawns = self[Piece::pawn(Color::Black)];
+        // Every pawn is stuck behind an enemy pawn
+        if (white_pawns << Dir::North) != black_pawns {
+            return false;
+        }
+
+        [Color::White, Color::Black].into_iter().all(|color| {
+            let enemy_pawns = self[Piece::pawn(!color)];
+            let enemy_attacks = self.piece_attacks(PieceKind::Pawn, !color, enemy_pawns, pawns);
+            let own_attacks =
+                self.piece_attacks(PieceKind::Pawn, color, self[Piece::pawn(color)], pawns);
+            if !(own_attacks & enemy_pawns).is_empty() {
+                return false;
+            }
+
+            // Flood fill every square the king could ever walk to
+            let free = !pawns & !enemy_attacks;
+            let Some(king) = self.king(color) else {
+                return false;
+            };
+            let mut region: Bitboard = king.into();
+            loop {
+                let next =
+                    region | self.piece_attacks(PieceKind::King, color, region, pawns) & free;
+                if next == region {
+                    break;
+                }
+                region = next;
+            }
+            (self.piece_attacks(PieceKind::King, color, region, pawns) & enemy_pawns).is_empty()
+        })
+    }
+
+    /// Returns true if the given [Color] could checkmate by some sequence of legal moves, even
+    /// with the help of the opponent. A player who runs out of time only loses if their opponent
+    /// could still checkmate them, and otherwise the game is drawn.
+    ///
+    /// A single minor piece or bishops all on squares of one color can only mate when the
+    /// opponent has pieces of their own to block their king in. Always true in variants other
+    /// than Standard, where running out of time loses regardless.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color};
+    /// let board = Board::from_fen("8/8/4k3/8/8/2N5/4K3/8 w - - 0 1")?;
+    /// assert!(!board.has_mating_material(Color::White));
+    ///
+    /// // The black pawn could block its own king in
+    /// let board = Board::from_fen("8/8/4k3/4p3/8/2N5/4K3/8 w - - 0 1")?;
+    /// assert!(board.has_mating_material(Color::White));
+    /// assert!(board.has_mating_material(Color::Black));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        if !self.variant.has_material_draws() {
+            return true;
+        }
+        if self.is_insufficient_material() {
+            return false;
+        }
+        let own = self[color] & !self[Piece::king(color)];
+        let heavy = self[Piece::pawn(color)] | self[Piece::rook(color)] | self[Piece::queen(color)];
+        if !heavy.is_empty() {
+            return true;
+        }
+
+        let knights = self[Piece::knight(color)];
+        let bishops = self[Piece::bishop(color)];
+        let one_color = knights.is_empty()
+            && ((bishops & LIGHT_SQUARES).is_empty() || (bishops & DARK_SQUARES).is_empty());
+        let lone_king = self[!color] == self[Piece::king(!color)];
+        !own.is_empty() && !((own.count_squares() == 1 || one_color) && lone_king)
+    }
+
+    fn pieces_of_kind(&self, kind: PieceKind) -> Bitboard {
+        self[Piece::Filled(kind, Color::White)] | self[Piece::Filled(kind, Color::Black)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Color, DrawReason, Outcome, WinReason};
+
+    #[test]
+    fn test_automatic_draws() {
+        // The 75-move rule ends the game on its own
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/4P3/R3K3 w - - 148 100").unwrap();
+        board.make("a1a2".parse().unwrap()).unwrap();
+        assert_eq!(board.outcome(), None);
+        assert_eq!(board.claimable_draw(), Some(DrawReason::FiftyMoveRule));
+        board.make("e8d7".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Draw(DrawReason::SeventyFiveMoveRule))
+        );
+        assert_eq!(board.claimable_draw(), None);
+
+        // Unless the last move was checkmate
+        let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 100").unwrap();
+        board.make("a1a8".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Win {
+                winner: Color::White,
+                reason: WinReason::Checkmate
+            })
+        );
+
+        // Fivefold repetition
+        let mut board = Board::default();
+        for _ in 0..4 {
+            for mv in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+                assert_eq!(board.outcome(), None);
+                board.make(mv.parse().unwrap()).unwrap();
+            }
+        }
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Draw(DrawReason::FivefoldRepetition))
+        );
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/3p4/4K3 w - - 0 1").unwrap();
+        board.make("e1d2".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Draw(DrawReason::InsufficientMaterial))
+        );
+    }
+
+    #[test]
+    fn test_insufficient_material() {
+        let cases = [
+            ("8/8/4k3/8/8/8/4K3/8 w - - 0 1", true),
+            ("8/8/4k3/8/8/5N2/4K3/8 w - - 0 1", true),
+            ("8/8/4k3/8/3b4/8/4K3/8 w - - 0 1", true),
+            // Bishops on the same color, on both sides
+            ("8/2b5/4k3/8/8/2B5/4KB2/8 w - - 0 1", true),
+            ("8/8/4k3/8/8/5NN1/4K3/8 w - - 0 1", false),
+            ("8/8/4k3/3n4/8/5N2/4K3/8 w - - 0 1", false),
+            ("8/8/4k3/8/8/2B5/4KN2/8 w - - 0 1", false),
+            ("8/8/4k3/8/8/8/4KP2/8 w - - 0 1", false),
+            ("8/8/4k3/8/8/8/4KR2/8 w - - 0 1", false),
+        ];
+        for (fen, expected) in cases {
+            let board = Board::from_fen(fen).unwrap();
+            assert_eq!(board.is_insufficient_material(), expected, "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_dead_position() {
+        let cases = [
+            ("8/4k3/8/1p1p1p1p/1P1P1P1P/8/4K3/8 w - - 0 1", true),
+            // A pawn can capture
+            ("8/4k3/8/1p1p1p1p/1P1P1PP1/8/4K3/8 w - - 0 1", false),
+            // A pawn can move
+            ("8/4k3/8/1p1p1p2/1P1P1P1P/8/4K3/8 w - - 0 1", false),
+            // The pawns on the g-file keep both kings out
+            ("8/4k3/8/p1p1p1p1/P1P1P1P1/8/4K3/8 w - - 0 1", true),
+            // Without them, the black king can walk around to the pawn on e4
+            ("8/4k3/8/p1p1p3/P1P1P3/8/4K3/8 w - - 0 1", false),
+            ("8/4k3/8/8/8/8/4K3/8 w - - 0 1", true),
+            ("8/4k3/8/8/8/8/4KQ2/8 w - - 0 1", false),
+        ];
+        for (fen, expected) in cases {
+            let board = Board::from_fen(fen).unwrap();
+            assert_eq!(board.is_dead_position(), expected, "{fen}");
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::{fmt, ops};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{move_gen, Bitboard, Board, Check, Color, Piece, Square, EMPTY};
+
+/// What one player is allowed to see of a [Board] in Fog of War. Squares outside of
+/// [FogView::visible] read as empty, whatever is really on them.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FogView {
+    viewer: Color,
+    color_to_move: Color,
+    visible: Bitboard,
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
+    pieces: [Piece; 64],
+}
+
+impl FogView {
+    /// The [Color] this view was made for
+    pub fn viewer(&self) -> Color {
+        self.viewer
+    }
+
+    pub fn color_to_move(&self) -> Color {
+        self.color_to_move
+    }
+
+    /// The squares the viewer can see
+    pub fn visible(&self) -> Bitboard {
+        self.visible
+    }
+
+    pub fn is_visible(&self, square: Square) -> bool {
+        self.visible.contains(square)
+    }
+}
+
+impl ops::Index<Square> for FogView {
+    type Output = Piece;
+
+    fn index(&self, index: Square) -> &Self::Output {
+        &self.pieces[index.index() as usize]
+    }
+}
+
+impl fmt::Display for FogView {
+    /// Writes the piece placement like the first field of a FEN, with `?` for every square the
+    /// viewer can't see
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rank in 0..8 {
+            let mut empty_squares = 0;
+            for index in ((rank << 3)..((rank + 1) << 3)).rev() {
+                let piece = self.pieces[index];
+                let square = Square::try_from(index).expect("Index is on the board");
+                if piece == Piece::Empty && self.visible.contains(square) {
+                    empty_squares += 1;
+                    continue;
+                }
+                if empty_squares != 0 {
+                    write!(f, "{empty_squares}")?;
+                    empty_squares = 0;
+                }
+                if piece == Piece::Empty {
+                    write!(f, "?")?;
+                } else {
+                    write!(f, "{piece}")?;
+                }
+            }
+            if empty_squares != 0 {
+                write!(f, "{empty_squares}")?;
+            }
+            if rank != 7 {
+                write!(f, "/")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Board {
+    /// Returns the squares the given [Color] can see in Fog of War: the squares its own pieces
+    /// stand on, and every square they could move to if it were that color's turn. Pins and
+    /// checks are ignored, since the pieces still see past them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color};
+    /// let board = Board::default();
+    ///
+    /// // Every piece can be seen, plus the four ranks pawns and knights can reach
+    /// assert_eq!(board.visible_squares(Color::White).count_squares(), 32);
+    /// assert!(board.visible_squares(Color::White).contains("e4".parse()?));
+    /// assert!(!board.visible_squares(Color::White).contains("e5".parse()?));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn visible_squares(&self, color: Color) -> Bitboard {
+        let mut board = self.clone();
+        if board.color_to_move != color {
+            board.modify(|m| {
+                m.set_ep_target(None);
+                m.toggle_color_to_move();
+            });
+        }
+        board.pins = EMPTY;
+        board.check = Check::None;
+        board.attacks = EMPTY;
+
+        move_gen::pseudo_legal(&board)
+            .into_iter()
+            .fold(self[color], |visible, mv| visible | mv.dest.into())
+    }
+
+    /// Creates the view of the board the given [Color] is allowed to see in Fog of War, with
+    /// every enemy piece outside of [Board::visible_squares] hidden.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color, Piece};
+    /// let mut board = Board::default();
+    /// board.make("e2e4".parse()?)?;
+    ///
+    /// let view = board.fog_view(Color::Black);
+    /// assert_eq!(view["e4".parse()?], Piece::Empty);
+    /// assert_eq!(view.to_string(), "rnbqkbnr/pppppppp/8/8/????????/????????/????????/????????");
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn fog_view(&self, color: Color) -> FogView {
+        let visible = self.visible_squares(color);
+        let mut pieces = [Piece::Empty; 64];
+        for square in visible {
+            pieces[square.index() as usize] = self[square];
+        }
+        FogView {
+            viewer: color,
+            color_to_move: self.color_to_move,
+            visible,
+            pieces,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoardBuilder, Color, Piece, Variant};
+
+    #[test]
+    fn test_visible_squares() {
+        let board = BoardBuilder::from_fen("4k3/8/8/3p4/2P1P3/8/8/R3K3 w Q - 0 1")
+            .unwrap()
+            .variant(Variant::FogOfWar)
+            .build()
+            .unwrap();
+        let visible = board.visible_squares(Color::White);
+        // Pawns see their captures and the square in front of them
+        assert!(visible.contains("d5".parse().unwrap()));
+        assert!(visible.contains("c5".parse().unwrap()));
+        assert!(!visible.contains("b5".parse().unwrap()));
+        // The rook sees up the file and castling shows the king's destination
+        assert!(visible.contains("a8".parse().unwrap()));
+        assert!(visible.contains("c1".parse().unwrap()));
+
+        let view = board.fog_view(Color::Black);
+        assert!(view.is_visible("c4".parse().unwrap()));
+        assert_eq!(view["c4".parse().unwrap()], Piece::pawn(Color::White));
+        assert_eq!(view["a1".parse().unwrap()], Piece::Empty);
+        assert_eq!(view.viewer(), Color::Black);
+        assert_eq!(view.color_to_move(), Color::White);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{Bitboard, Board, CheckKind, Move, Piece, PieceKind, Ray, Square, EMPTY};
+
+const CHECKING_KINDS: [PieceKind; 5] = [
+    PieceKind::Pawn,
+    PieceKind::Knight,
+    PieceKind::Bishop,
+    PieceKind::Rook,
+    PieceKind::Queen,
+];
+
+impl Board {
+    /// Precomputes the squares each kind of piece of the side to move would give check from,
+    /// and which of its pieces block a check from one of its own sliders
+    #[inline]
+    pub(super) fn update_check_info(&mut self) {
+        let color = self.color_to_move;
+        let king = match self.king(!color) {
+            Some(king) if self.variant.has_check() => king,
+            _ => {
+                self.check_squares = [EMPTY; 6];
+                self.discovered_blockers = EMPTY;
+                return;
+            }
+        };
+        let initial: Bitboard = king.into();
+        let occupancy = !self[Piece::Empty];
+
+        // A piece attacks the king from every square the same piece on the king's square would
+        // attack, with pawns looking the other way
+        for kind in CHECKING_KINDS {
+            self.check_squares[kind as usize] =
+                self.piece_attacks(kind, !color, initial, occupancy);
+        }
+        self.check_squares[PieceKind::King as usize] = EMPTY;
+
+        let queens = self[Piece::queen(color)];
+        let snipers = self.piece_attacks(PieceKind::Bishop, color, initial, EMPTY)
+            & (self[Piece::bishop(color)] | queens)
+            | self.piece_attacks(PieceKind::Rook, color, initial, EMPTY)
+                & (self[Piece::rook(color)] | queens);
+        let mut blockers = EMPTY;
+        for sniper in snipers {
+            let between = Bitboard::between(king, sniper) & occupancy;
+            if between.count_squares() == 1 && !(between & self[color]).is_empty() {
+                blockers |= between;
+            }
+        }
+        self.discovered_blockers = blockers;
+    }
+
+    /// Returns true if making the legal move `mv` would put the opponent in check. The position
+    /// is not changed, so this is much cheaper than making and unmaking the move.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let board = Board::from_fen("r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5Q2/PPPP1PPP/RNB1K1NR w KQkq - 2 3")?;
+    ///
+    /// assert!(board.gives_check("f3f7".parse()?));
+    /// assert!(!board.gives_check("f3f6".parse()?));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn gives_check(&self, mv: Move) -> bool {
+        self.gives_check_kind(mv).is_some()
+    }
+
+    /// Returns how making the legal move `mv` would check the opponent, or `None` if it wouldn't.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, CheckKind};
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/4B3/4R1K1 w - - 0 1")?;
+    ///
+    /// assert_eq!(board.gives_check_kind("e2b5".parse()?), Some(CheckKind::Double));
+    /// assert_eq!(board.gives_check_kind("e2d3".parse()?), Some(CheckKind::Discovered));
+    /// assert_eq!(board.gives_check_kind("g1f2".parse()?), None);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn gives_check_kind(&self, mv: Move) -> Option<CheckKind> {
+        let king = self.king(!self.color_to_move)?;
+        if !self.variant.has_check() || mv.is_null() {
+            return None;
+        }
+
+        if mv.is_drop() {
+            let kind = mv.promotion.kind()?;
+            return self.check_squares[kind as usize]
+                .contains(mv.dest)
+                .then_some(CheckKind::Direct);
+        }
+
+        let kind = self[mv.origin].kind()?;
+        let is_castle = kind == PieceKind::King && mv.origin.index().abs_diff(mv.dest.index()) == 2;
+        let is_ep = kind == PieceKind::Pawn && Some(mv.dest) == self.ep_target;
+        if is_castle || is_ep || mv.promotion != Piece::Empty {
+            return self.changed_lines_check_kind(mv, kind, king, is_castle);
+        }
+
+        let direct = self.check_squares[kind as usize].contains(mv.dest);
+        // A blocker only uncovers the check if it steps off the line to the king
+        let discovered = self.discovered_blockers.contains(mv.origin)
+            && !Ray::from(king, mv.origin).is_some_and(|ray| Bitboard::from(ray).contains(mv.dest));
+        check_kind(direct, discovered)
+    }
+
+    /// Castling, en passant and promotions change more than the moved piece's square, so the
+    /// attacks on the king are worked out from the occupancy after the move
+    fn changed_lines_check_kind(
+        &self,
+        mv: Move,
+        kind: PieceKind,
+        king: Square,
+        is_castle: bool,
+    ) -> Option<CheckKind> {
+        let color = self.color_to_move;
+        let mut occupancy = !self[Piece::Empty] ^ mv.origin.into() | mv.dest.into();
+
+        let (square, kind) = if is_castle {
+            let (rook_origin, rook_dest) = if mv.dest.index() < mv.origin.index() {
+                (mv.origin.index() - 3, mv.origin.index() - 1)
+            } else {
+                (mv.origin.index() + 4, mv.origin.index() + 1)
+            };
+            let rook_origin = Square::try_from(rook_origin).ok()?;
+            let rook_dest = Square::try_from(rook_dest).ok()?;
+            occupancy ^= Bitboard::from(rook_origin) | rook_dest.into();
+            (rook_dest, PieceKind::Rook)
+        } else {
+            if self.ep_target == Some(mv.dest) && kind == PieceKind::Pawn {
+                occupancy ^= self.ep_victim();
+            }
+            (mv.dest, mv.promotion.kind().unwrap_or(kind))
+        };
+
+        let direct = self
+            .piece_attacks(kind, color, square.into(), occupancy)
+            .contains(king);
+        // Any other piece now attacking the king was uncovered by the move
+        let discovered = !(self.attackers_to(king, occupancy) & self[color]).is_empty();
+        check_kind(direct, discovered)
+    }
+}
+
+#[inline(always)]
+fn check_kind(direct: bool, discovered: bool) -> Option<CheckKind> {
+    match (direct, discovered) {
+        (true, true) => Some(CheckKind::Double),
+        (true, false) => Some(CheckKind::Direct),
+        (false, true) => Some(CheckKind::Discovered),
+        (false, false) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Check, CheckKind};
+
+    fn compare_with_make(board: &mut Board, depth: usize) {
+        for mv in board.legal_moves() {
+            let kind = board.gives_check_kind(mv);
+            unsafe {
+                board.make_unchecked(mv);
+            }
+            let expected = match board.check() {
+                Check::None => None,
+                Check::Double => Some(CheckKind::Double),
+                Check::Single(_) => kind.filter(|k| *k != CheckKind::Double),
+            };
+            assert_eq!(kind, expected, "{mv} before {}", board.to_fen());
+            if depth > 1 {
+                compare_with_make(board, depth - 1);
+            }
+            board.unmake();
+        }
+    }
+
+    #[test]
+    fn test_gives_check_matches_make() {
+        let fens = [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            // Castling with check, en passant discovering check and promotions behind the pawn
+            "4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1",
+            "8/8/8/1k1pP1R1/8/8/8/4K3 w - d6 0 1",
+            "8/4P3/8/8/8/8/8/K3k3 w - - 0 1",
+        ];
+        for fen in fens {
+            compare_with_make(&mut Board::from_fen(fen).unwrap(), 3);
+        }
+    }
+}
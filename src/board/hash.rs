@@ -1,53 +1,277 @@
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
 use rand::{self, rngs::StdRng, RngCore, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::piece::Color;
-use crate::{piece::Piece, Board};
-use crate::{Castle, Square};
+use crate::{move_gen, piece::Piece, Board};
+use crate::{Castle, Dir, Square};
 
 pub(crate) const MAX_PIECE_INDEX: usize = 767;
-const SEED: [u8; 32] = [
+/// The seed of the keys boards hash with unless they are given others
+pub const DEFAULT_SEED: [u8; 32] = [
     148, 94, 120, 126, 227, 253, 25, 236, 41, 96, 70, 10, 53, 197, 51, 231, 204, 44, 136, 210, 102,
     129, 128, 230, 251, 207, 200, 134, 166, 125, 236, 147,
 ];
+/// The number of keys in a [ZobristKeys] table
+pub const ZOBRIST_KEY_COUNT: usize = 781;
+
+/// A 64-bit Zobrist hash
+pub type ZobristHash = u64;
+/// A 128-bit Zobrist hash, whose low 64 bits are the [ZobristHash] of the same position
+pub type ZobristHash128 = u128;
+
+/// The hash boards keep and compare repetitions with: all 128 bits with the `zobrist128`
+/// feature, and the low 64 otherwise so that boards stay small. No public type depends on it,
+/// so the feature only adds the `hash128` methods of [Board] and [Position](crate::Position).
+#[cfg(not(feature = "zobrist128"))]
+pub(crate) type StoredHash = ZobristHash;
+#[cfg(feature = "zobrist128")]
+pub(crate) type StoredHash = ZobristHash128;
 
-// May make the zobrist key generation public so other board representations can share same keys?
-#[inline]
-pub(super) fn zobrist_keys() -> [u64; 781] {
-    seeded_zobrist_keys(SEED)
+/// The keys boards hash with when they aren't given others
+static DEFAULT_KEYS: OnceLock<Arc<ZobristKeys>> = OnceLock::new();
+
+/// The random keys a [Board] is hashed with. The hash of a position is the XOR of the keys of
+/// everything in it, with the table laid out as follows:
+///
+/// | Index     | Key for                                                                 |
+/// |-----------|-------------------------------------------------------------------------|
+/// | 0..768    | A piece on a square, at `64 * piece.index() + square.index()`           |
+/// | 768       | Black to move                                                           |
+/// | 769..773  | Castling rights: white king side, white queen side, black king side, black queen side |
+/// | 773..781  | The file of the en passant target, from the h file to the a file        |
+///
+/// [Piece::index] and [Square::index] give the indices, so squares count from h8 to a1. The
+/// en passant key is only used when the side to move can legally capture en passant, since
+/// the position is otherwise the same as without a target, as FIDE rules count repetitions.
+///
+/// Each key is 128 bits wide, and its low 64 bits are the key for the 64-bit hash, so the
+/// 64-bit and 128-bit hashes of a position agree on their low bits. Boards keep the 64-bit hash
+/// unless the `zobrist128` feature is enabled. The keys are drawn from [StdRng]
+/// seeded with [DEFAULT_SEED] unless a board is built with others: the low halves of all the
+/// keys first, then the high halves. A table from another program can be loaded with
+/// [ZobristKeys::from_table] or [ZobristKeys::from_table128] to get the same hashes it does.
+///
+/// Boards share their keys through an [Arc], so any number of boards can use one set. A board
+/// with other keys than the default ones is serialized with its keys, so it reads back with
+/// the same hashes. Boards are only equal if they use equal keys, since their hashes would
+/// differ otherwise.
+///
+/// # Examples
+/// ```
+/// # use std::sync::Arc;
+/// # use chb_chess::{Board, BoardBuilder, ZobristKeys};
+/// let keys = Arc::new(ZobristKeys::seeded([7; 32]));
+/// let board = BoardBuilder::default().zobrist_keys(keys.clone()).build()?;
+///
+/// assert_eq!(board.hash(), keys.hash(&board));
+/// assert_ne!(board.hash(), Board::default().hash());
+/// assert_ne!(board, Board::default());
+///
+/// let mut rehashed = Board::default();
+/// rehashed.set_zobrist_keys(keys);
+/// assert_eq!(rehashed.hash(), board.hash());
+/// assert_eq!(rehashed, board);
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ZobristKeys {
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
+    table: [ZobristHash128; ZOBRIST_KEY_COUNT],
 }
-#[inline]
-pub(super) fn seeded_zobrist_keys(seed: [u8; 32]) -> [u64; 781] {
-    let mut keys = [0u64; 781];
-    let mut rng = StdRng::from_seed(seed);
-    keys.iter_mut().for_each(|key| {
-        *key = rng.next_u64();
-    });
-    keys
+
+impl ZobristKeys {
+    /// Draws the keys from [StdRng] seeded with `seed`
+    pub fn seeded(seed: [u8; 32]) -> Self {
+        let mut table = [0; ZOBRIST_KEY_COUNT];
+        let mut rng = StdRng::from_seed(seed);
+        table.iter_mut().for_each(|key| {
+            *key = rng.next_u64() as ZobristHash128;
+        });
+        table.iter_mut().for_each(|key| {
+            *key |= (rng.next_u64() as ZobristHash128) << 64;
+        });
+        Self { table }
+    }
+
+    /// Uses a table of 64-bit keys laid out as described for [ZobristKeys], leaving the high
+    /// bits of the 128-bit keys at 0
+    pub const fn from_table(table: [ZobristHash; ZOBRIST_KEY_COUNT]) -> Self {
+        let mut wide = [0; ZOBRIST_KEY_COUNT];
+        let mut i = 0;
+        while i < ZOBRIST_KEY_COUNT {
+            wide[i] = table[i] as ZobristHash128;
+            i += 1;
+        }
+        Self { table: wide }
+    }
+
+    /// Uses a table of 128-bit keys laid out as described for [ZobristKeys]
+    pub const fn from_table128(table: [ZobristHash128; ZOBRIST_KEY_COUNT]) -> Self {
+        Self { table }
+    }
+
+    /// The keys boards hash with unless they are given others
+    pub fn default_keys() -> &'static Arc<Self> {
+        DEFAULT_KEYS.get_or_init(|| Arc::new(Self::seeded(DEFAULT_SEED)))
+    }
+
+    pub fn table(&self) -> &[ZobristHash128; ZOBRIST_KEY_COUNT] {
+        &self.table
+    }
+
+    /// The key for a piece on a square, or 0 for [Piece::Empty]
+    pub fn piece(&self, piece: Piece, square: Square) -> ZobristHash128 {
+        match piece {
+            Piece::Empty => 0,
+            _ => self.table[hash_index(piece, square.index().into())],
+        }
+    }
+
+    /// The key for Black to move
+    pub fn black_to_move(&self) -> ZobristHash128 {
+        self.table[MAX_PIECE_INDEX + 1]
+    }
+
+    /// The keys for the castling rights a player has
+    pub fn castle(&self, color: Color, castle: Castle) -> ZobristHash128 {
+        let index = match color {
+            Color::White => MAX_PIECE_INDEX + 2,
+            Color::Black => MAX_PIECE_INDEX + 4,
+        };
+        let king_side = match castle.get_king_side() {
+            true => self.table[index],
+            false => 0,
+        };
+        let queen_side = match castle.get_queen_side() {
+            true => self.table[index + 1],
+            false => 0,
+        };
+        king_side ^ queen_side
+    }
+
+    /// The key for an en passant target on the file of `square`
+    pub fn ep_target(&self, square: Square) -> ZobristHash128 {
+        self.table[MAX_PIECE_INDEX + 6 + square.file() as usize]
+    }
+
+    /// Hashes a board from scratch with these keys, whichever keys the board itself uses
+    pub fn hash(&self, board: &Board) -> ZobristHash {
+        self.hash128(board) as ZobristHash
+    }
+
+    /// Hashes a board from scratch with the full 128-bit keys
+    pub fn hash128(&self, board: &Board) -> ZobristHash128 {
+        let mut hash = board
+            .pieces
+            .iter()
+            .enumerate()
+            .map(|(sq, p)| self.piece(*p, sq.try_into().expect("Index is on the board")))
+            .fold(0, |hash, key| hash ^ key);
+        if board.color_to_move == Color::Black {
+            hash ^= self.black_to_move();
+        }
+        hash ^= self.castle(Color::White, board.castle[Color::White]);
+        hash ^= self.castle(Color::Black, board.castle[Color::Black]);
+        if let Some(target) = hashed_ep_target(board) {
+            hash ^= self.ep_target(target);
+        }
+        hash
+    }
 }
 
-pub(super) fn toggle_color_hash(board: &mut Board) {
-    board.hash ^= board.hash_keys[MAX_PIECE_INDEX + 1];
+impl Default for ZobristKeys {
+    fn default() -> Self {
+        Self::default_keys().as_ref().clone()
+    }
 }
 
-pub(super) fn update_castle_hash(board: &mut Board, color: Color, old: Castle, new: Castle) {
-    let index = match color {
-        Color::White => MAX_PIECE_INDEX + 2,
-        Color::Black => MAX_PIECE_INDEX + 4,
-    };
-    if old.get_king_side() != new.get_king_side() {
-        board.hash ^= board.hash_keys[index];
+impl PartialEq for ZobristKeys {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other) || self.table == other.table
     }
-    if old.get_queen_side() != new.get_queen_side() {
-        board.hash ^= board.hash_keys[index + 1];
+}
+
+impl fmt::Debug for ZobristKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZobristKeys").finish_non_exhaustive()
     }
 }
 
+/// The default keys, for serde to give the fields it skips
+#[cfg(feature = "serde")]
+pub(super) fn default_keys() -> Arc<ZobristKeys> {
+    ZobristKeys::default_keys().clone()
+}
+
+/// Serializes the keys of a board only if they aren't the default ones
+#[cfg(feature = "serde")]
+pub(super) mod serde_keys {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        keys: &Arc<ZobristKeys>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let custom = (keys != ZobristKeys::default_keys()).then_some(keys.as_ref());
+        custom.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<ZobristKeys>, D::Error> {
+        let custom = Option::<ZobristKeys>::deserialize(deserializer)?;
+        Ok(custom.map_or_else(default_keys, Arc::new))
+    }
+}
+
+/// The part of a key that boards keep
+#[inline(always)]
+pub(crate) fn stored(key: ZobristHash128) -> StoredHash {
+    key as StoredHash
+}
+
+pub(super) fn toggle_color_hash(board: &mut Board) {
+    board.hash ^= stored(board.keys.black_to_move());
+}
+
+pub(super) fn update_castle_hash(board: &mut Board, color: Color, old: Castle, new: Castle) {
+    board.hash ^= stored(board.keys.castle(color, old) ^ board.keys.castle(color, new));
+}
+
 pub(super) fn toggle_ep_hash(board: &mut Board, square: Square) {
-    board.hash ^= board.hash_keys[MAX_PIECE_INDEX + 6 + square.file() as usize];
+    board.hash ^= stored(board.keys.ep_target(square));
+}
+
+/// The en passant target if the side to move can legally capture on it, which is when its key
+/// is in the hash
+pub(super) fn hashed_ep_target(board: &Board) -> Option<Square> {
+    let target = board.ep_target?;
+    let color = board.color_to_move;
+    let behind = match color {
+        Color::White => [Dir::SouEast, Dir::SouWest],
+        Color::Black => [Dir::NorEast, Dir::NorWest],
+    };
+    behind
+        .into_iter()
+        .filter_map(|dir| target.checked_add(dir))
+        .filter(|sq| board[*sq] == Piece::pawn(color))
+        .any(|sq| {
+            move_gen::for_square(board, sq)
+                .iter()
+                .any(|mv| mv.dest == target)
+        })
+        .then_some(target)
 }
 
 pub(super) fn increment_hash(board: &mut Board, piece: Piece, square: Square) {
-    board.hash ^= board.hash_keys[hash_index(piece, square.index().into())];
+    board.hash ^= stored(board.keys.piece(piece, square));
 }
 
 pub(super) fn hash_index(p: Piece, index: usize) -> usize {
@@ -56,9 +280,9 @@ pub(super) fn hash_index(p: Piece, index: usize) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{str::FromStr, sync::Arc};
 
-    use crate::{moves::Move, Board};
+    use crate::{moves::Move, Board, ZobristKeys};
 
     #[test]
     fn test_hash() {
@@ -83,4 +307,51 @@ mod tests {
         }
         assert_eq!(initial, board.hash);
     }
+
+    #[test]
+    fn test_shared_keys() {
+        // Boards built separately hash with the same keys
+        let mut board = Board::new();
+        board.make(Move::from_str("e2e4").unwrap()).unwrap();
+        let fen = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        assert_eq!(fen.unwrap().hash, board.hash);
+    }
+
+    #[test]
+    fn test_full_hash() {
+        // The incremental hash matches hashing from scratch, including castling, en passant and
+        // Black to move
+        let mut board = Board::from_fen("r3k2r/8/8/8/1p6/8/P7/R3K2R w KQkq - 0 1").unwrap();
+        let keys = ZobristKeys::default_keys();
+        for mv in ["a2a4", "e8g8", "e1c1"] {
+            board.make(mv.parse().unwrap()).unwrap();
+            assert_eq!(keys.hash(&board), board.hash());
+            #[cfg(feature = "zobrist128")]
+            assert_eq!(keys.hash128(&board), board.hash128());
+        }
+    }
+
+    #[test]
+    fn test_custom_keys() {
+        let keys = Arc::new(ZobristKeys::seeded([1; 32]));
+        let mut board = Board::new();
+        for m in ["g1f3", "g8f6", "f3g1", "f6g8", "g1f3"] {
+            board.make(m.parse().unwrap()).unwrap();
+        }
+        board.set_zobrist_keys(keys.clone());
+        assert_eq!(keys.hash(&board), board.hash());
+        board.make("f6g8".parse().unwrap()).unwrap_err();
+        board.make("g8f6".parse().unwrap()).unwrap();
+        assert_eq!(board.repetition_count(), 2);
+
+        let table = ZobristKeys::from_table128(*keys.table());
+        assert_eq!(table, *keys);
+        assert_ne!(table, **ZobristKeys::default_keys());
+
+        // 64-bit keys hash the same as the low halves of the 128-bit ones
+        let low = keys.table().map(|key| key as u64);
+        let narrow = ZobristKeys::from_table(low);
+        assert_eq!(narrow.hash(&board), board.hash());
+        assert_eq!(narrow.hash128(&board), board.hash() as u128);
+    }
 }
@@ -0,0 +1,266 @@
+use super::Board;
+use crate::{
+    move_gen, squares, Check, Color, Dir, Move, MoveError, Piece, PieceKind, Ray, Square, EMPTY,
+};
+
+impl Board {
+    /// Explains why a move can't be made, or returns `None` if it is legal. The reasons are
+    /// specific enough to show to a player: the square that blocks a piece, the [Ray] a piece
+    /// is pinned along, the piece giving check, or why castling isn't allowed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, MoveError};
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/4r3/R3K2R w KQ - 0 1")?;
+    ///
+    /// let castle = "e1g1".parse()?;
+    /// assert_eq!(board.why_illegal(castle), Some(MoveError::CastlingOutOfCheck(castle)));
+    ///
+    /// let rook = "a1a3".parse()?;
+    /// assert_eq!(board.why_illegal(rook), Some(MoveError::InCheck(rook, "e2".parse()?)));
+    ///
+    /// assert_eq!(board.why_illegal("e1e2".parse()?), None);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn why_illegal(&self, mv: Move) -> Option<MoveError> {
+        self.check_legal(mv).err()
+    }
+
+    /// Works out why a move of a piece of the side to move isn't legal
+    pub(crate) fn illegal_reason(&self, mv: Move) -> MoveError {
+        let piece = self[mv.origin];
+        if piece.is_kind(PieceKind::King) && self.variant.has_castling() {
+            if let Some(err) = self.castling_error(mv) {
+                return err;
+            }
+        }
+
+        // Without pins and attacks, only the movement rules of the pieces are left. An en passant
+        // capture that would uncover the king along its rank is still left out by move
+        // generation, so it is let through here to be explained like any other pin.
+        let mut unconstrained = self.clone();
+        unconstrained.pins = EMPTY;
+        unconstrained.check = Check::None;
+        unconstrained.attacks = EMPTY;
+        let ep_capture = piece.is_kind(PieceKind::Pawn)
+            && Some(mv.dest) == self.ep_target
+            && mv.origin.file() != mv.dest.file()
+            && mv.promotion == Piece::Empty
+            && self.movement(mv).is_some();
+
+        if !ep_capture && !move_gen::pseudo_legal(&unconstrained).contains(&mv) {
+            self.movement_error(mv)
+        } else if self.variant.has_forced_captures() {
+            MoveError::CaptureRequired(mv)
+        } else if !self.variant.allows_giving_check() && self.gives_check(mv) {
+            MoveError::GivesCheck(mv)
+        } else if piece.is_kind(PieceKind::King) {
+            MoveError::KingIntoAttack(mv)
+        } else if let Some(ray) = self.pin_on_square(mv.origin) {
+            MoveError::Pinned(mv, ray)
+        } else {
+            match self.check {
+                Check::Single(checker) => MoveError::InCheck(mv, checker),
+                Check::Double => MoveError::DoubleCheck(mv),
+                Check::None => MoveError::LeavesKingInCheck(mv),
+            }
+        }
+    }
+
+    /// Explains why a king move two squares along its first rank can't castle, if it is one
+    fn castling_error(&self, mv: Move) -> Option<MoveError> {
+        let color = self.color_to_move;
+        let start: Square = match color {
+            Color::White => squares::E1,
+            Color::Black => squares::E8,
+        };
+        if mv.origin != start || mv.origin.rank() != mv.dest.rank() {
+            return None;
+        }
+        // Files count from the h file, so the king side is toward lower indices
+        let (king_side, rook) = match mv.dest.index() as i32 - mv.origin.index() as i32 {
+            -2 => (true, mv.origin.index() - 3),
+            2 => (false, mv.origin.index() + 4),
+            _ => return None,
+        };
+
+        let castle = self.castle[color];
+        if !(king_side && castle.get_king_side() || !king_side && castle.get_queen_side()) {
+            return Some(MoveError::CastlingRights(mv));
+        }
+        let rook = Square::try_from(rook as usize).expect("The rook is on the king's rank");
+        let path = Ray::from(mv.origin, rook).expect("The king and rook share a rank");
+        if let Some(blocker) = path
+            .into_iter()
+            .take_while(|sq| *sq != rook)
+            .find(|sq| self[*sq] != Piece::Empty)
+        {
+            return Some(MoveError::Blocked(mv, blocker));
+        }
+
+        if !self.variant.has_check() {
+            return None;
+        }
+        if self.check != Check::None {
+            return Some(MoveError::CastlingOutOfCheck(mv));
+        }
+        path.into_iter()
+            .take(2)
+            .find(|sq| self.attacks.contains(*sq))
+            .map(|sq| MoveError::CastlingThroughCheck(mv, sq))
+    }
+
+    /// Checks that a move fits the way the piece on its origin moves, whatever stands on the
+    /// squares it passes or lands on. Castling isn't covered. Returns the path the piece moves
+    /// along, if it slides or pushes, and whether it is a pawn push.
+    pub(super) fn movement(&self, mv: Move) -> Option<(Option<Ray>, bool)> {
+        let color = self[mv.origin].color()?;
+        let kind = self[mv.origin].kind()?;
+        let files = mv.origin.file().abs_diff(mv.dest.file());
+        let ranks = mv.origin.rank().abs_diff(mv.dest.rank());
+
+        let (fits, path, pushes) = match kind {
+            PieceKind::Knight => (files.min(ranks) == 1 && files.max(ranks) == 2, None, false),
+            PieceKind::King => (files.max(ranks) == 1, None, false),
+            PieceKind::Pawn => {
+                let (forward, start_ranks) = match color {
+                    Color::White => (Dir::North, [6, 7]),
+                    Color::Black => (Dir::South, [1, 1]),
+                };
+                let step = mv.origin.checked_add(forward);
+                let double = step.and_then(|sq| sq.checked_add(forward)).filter(|_| {
+                    mv.origin.rank() == start_ranks[0]
+                        || self.variant.has_first_rank_double_push()
+                            && mv.origin.rank() == start_ranks[1]
+                });
+                let capture = step.is_some_and(|sq| sq.rank() == mv.dest.rank()) && files == 1;
+                let push = step == Some(mv.dest) || double == Some(mv.dest);
+                let path = Ray {
+                    origin: mv.origin,
+                    dir: forward,
+                };
+                (push || capture, push.then_some(path), push)
+            }
+            _ => match Ray::from(mv.origin, mv.dest) {
+                Some(ray) if kind == PieceKind::Queen || ray.dir.piece_kind() == kind => {
+                    (true, Some(ray), false)
+                }
+                _ => (false, None, false),
+            },
+        };
+        fits.then_some((path, pushes))
+    }
+
+    /// Explains why a move breaks the movement rules of the piece: it can't move that way, a
+    /// piece is in its way, it would capture its own side, or it needs a promotion
+    fn movement_error(&self, mv: Move) -> MoveError {
+        let color = self.color_to_move;
+        let Some((path, pushes)) = self.movement(mv) else {
+            return MoveError::IllegalDestination(mv);
+        };
+
+        // Pawns can't capture by pushing, so the destination blocks them too
+        let blocker = path.and_then(|path| {
+            for sq in path {
+                if sq == mv.dest && !pushes {
+                    break;
+                }
+                if self[sq] != Piece::Empty {
+                    return Some(sq);
+                }
+                if sq == mv.dest {
+                    break;
+                }
+            }
+            None
+        });
+        if let Some(blocker) = blocker {
+            MoveError::Blocked(mv, blocker)
+        } else if self[mv.dest].is_color(color) {
+            MoveError::CaptureOwnPiece(mv)
+        } else if mv.promotion == Piece::Empty && self.is_promotion(mv.origin, mv.dest) {
+            MoveError::PromotionRequired(mv)
+        } else {
+            MoveError::IllegalDestination(mv)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Move, MoveError};
+
+    /// The reason a move is expected to be illegal, if any
+    type Expected = fn(Move) -> Option<MoveError>;
+
+    fn reasons(fen: &str, cases: &[(&str, Expected)]) {
+        let board = Board::from_fen(fen).unwrap();
+        for (mv, reason) in cases {
+            let mv = mv.parse().unwrap();
+            assert_eq!(board.why_illegal(mv), reason(mv), "{mv}");
+        }
+    }
+
+    #[test]
+    fn test_movement() {
+        reasons(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[
+                ("a1a3", |mv| {
+                    Some(MoveError::Blocked(mv, "a2".parse().unwrap()))
+                }),
+                ("f1c4", |mv| {
+                    Some(MoveError::Blocked(mv, "e2".parse().unwrap()))
+                }),
+                ("g1e2", |mv| Some(MoveError::CaptureOwnPiece(mv))),
+                ("b1b3", |mv| Some(MoveError::IllegalDestination(mv))),
+                ("e2d3", |mv| Some(MoveError::IllegalDestination(mv))),
+                ("e2e5", |mv| Some(MoveError::IllegalDestination(mv))),
+                ("e2e4", |_| None),
+            ],
+        );
+        reasons(
+            "4k3/8/8/8/8/4p3/4P3/4K3 w - - 0 1",
+            &[
+                ("e2e3", |mv| {
+                    Some(MoveError::Blocked(mv, "e3".parse().unwrap()))
+                }),
+                ("e2e4", |mv| {
+                    Some(MoveError::Blocked(mv, "e3".parse().unwrap()))
+                }),
+            ],
+        );
+        // Capturing en passant would uncover the king along the fifth rank
+        reasons(
+            "8/8/8/K2pP2r/8/8/8/7k w - d6 0 2",
+            &[("e5d6", |mv| Some(MoveError::LeavesKingInCheck(mv)))],
+        );
+        // A rook and a bishop both check the king, so the knight can't help
+        reasons(
+            "4k3/8/8/8/1b6/8/8/r3K2N w - - 0 1",
+            &[("h1g3", |mv| Some(MoveError::DoubleCheck(mv)))],
+        );
+    }
+
+    #[test]
+    fn test_castling() {
+        reasons(
+            "r3k2r/8/8/8/8/8/5r2/RN2K2R w Kkq - 0 1",
+            &[
+                ("e1c1", |mv| Some(MoveError::CastlingRights(mv))),
+                ("e1g1", |mv| {
+                    Some(MoveError::CastlingThroughCheck(mv, "f1".parse().unwrap()))
+                }),
+            ],
+        );
+        reasons(
+            "r3k2r/8/8/8/8/8/8/RN2K2R w KQkq - 0 1",
+            &[
+                ("e1c1", |mv| {
+                    Some(MoveError::Blocked(mv, "b1".parse().unwrap()))
+                }),
+                ("e1g1", |_| None),
+            ],
+        );
+    }
+}
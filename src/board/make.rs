@@ -1,10 +1,9 @@
 use crate::{
-    error::{BoardError, ErrorKind},
+    error::{BoardError, MoveError},
     move_gen,
     moves::{Move, MoveState},
     piece::{Color, Piece, PieceKind},
-    square::Square,
-    squares, Board, Castle,
+    squares, Board, Castle, Check,
 };
 
 impl Board {
@@ -12,34 +11,135 @@ impl Board {
     ///
     /// # Errors
     ///
-    /// Returns a [BoardError] if the move is not legal
+    /// Returns a [BoardError::Move] saying why the move is not legal
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, BoardError, Dir, MoveError, Ray};
+    /// let mut board = Board::from_fen("4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1")?;
+    ///
+    /// let mv = "e2d3".parse()?;
+    /// let pin = Ray { origin: "e1".parse()?, dir: Dir::North };
+    /// assert_eq!(board.make(mv), Err(BoardError::Move(MoveError::Pinned(mv, pin))));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
     pub fn make(&mut self, mv: Move) -> Result<(), BoardError> {
+        self.check_legal(mv)?;
+        //Move is valid, and legal
+        unsafe {
+            self.make_unchecked(mv);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a move is legal, working out why it isn't if not
+    pub(crate) fn check_legal(&self, mv: Move) -> Result<(), MoveError> {
         let piece = self[mv.origin];
+        if piece == Piece::Empty {
+            return Err(MoveError::NoPiece(mv.origin));
+        }
         if !piece.is_color(self.color_to_move()) {
-            // Piece is not empty and matches color
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Attempted to move wrong color",
-            ));
+            return Err(MoveError::WrongColor(mv.origin));
         }
         let moves = move_gen::for_square(self, mv.origin);
-        if !moves.contains(&mv) {
-            return Err(BoardError::new(ErrorKind::InvalidInput, "Invalid move"));
+        if moves.contains(&mv) {
+            return Ok(());
         }
-        //Move is valid, and legal
-        unsafe {
-            self.make_unchecked(mv);
+
+        Err(self.illegal_reason(mv))
+    }
+
+    /// Passes the turn to the opponent without moving, as null-move pruning does in a search. The
+    /// en passant target is cleared, and the null move is recorded in the history so that
+    /// [Board::unmake] or [Board::unmake_null] can take it back.
+    ///
+    /// # Errors
+    ///
+    /// Returns [MoveError::PassInCheck] if the side to move is in check, since passing would let
+    /// the king be captured.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Color};
+    /// let mut board = Board::default();
+    /// board.make("e2e4".parse()?)?;
+    ///
+    /// board.make_null()?;
+    /// assert_eq!(board.color_to_move(), Color::White);
+    /// assert_eq!(board.ep_target(), None);
+    ///
+    /// board.unmake_null();
+    /// assert_eq!(board.color_to_move(), Color::Black);
+    /// assert_eq!(board.ep_target(), Some("e3".parse()?));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn make_null(&mut self) -> Result<(), BoardError> {
+        if self.check != Check::None {
+            return Err(MoveError::PassInCheck.into());
         }
+        let color = self.color_to_move;
+        let hash = self.hash;
+        let ms = self.modify(|modifier| -> MoveState {
+            let move_state = MoveState {
+                mv: Move::NULL,
+                capture: Piece::Empty,
+                castle: modifier.board().castle,
+                halfmove: modifier.board().halfmove,
+                ep_target: modifier.board().ep_target,
+                hash,
+            };
+            modifier.toggle_color_to_move();
+            modifier.set_ep_target(None);
+            move_state
+        });
 
+        self.move_history.push(ms);
+        if color == Color::Black {
+            self.fullmove += 1;
+        }
+        self.halfmove += 1;
         Ok(())
     }
 
-    /// Unmakes the last move made. If no moves have been made, nothing happens.
+    /// Takes back a null move made with [Board::make_null]. If the last move was not a null move,
+    /// nothing happens.
+    pub fn unmake_null(&mut self) {
+        if matches!(self.move_history.last(), Some(ms) if ms.mv.is_null()) {
+            self.unmake();
+        }
+    }
+
+    /// Unmakes the last move made, including null moves. If no moves have been made, nothing
+    /// happens.
     pub fn unmake(&mut self) {
         let ms = match self.move_history.pop() {
             Some(m) => m,
             None => return,
         };
+        if ms.mv.is_null() {
+            let color = self.modify(|modifier| {
+                modifier.set_ep_target(ms.ep_target);
+                modifier.toggle_color_to_move()
+            });
+            if color == Color::Black {
+                self.fullmove -= 1;
+            }
+            self.halfmove = ms.halfmove;
+            return;
+        }
+        if ms.mv.is_drop() {
+            self.modify(|modifier| {
+                modifier.clear(ms.mv.dest);
+                modifier.toggle_color_to_move();
+                modifier.set_ep_target(ms.ep_target);
+            });
+            if ms.mv.promotion.is_color(Color::Black) {
+                self.fullmove -= 1;
+            }
+            self.halfmove = ms.halfmove;
+            return;
+        }
         let piece @ Piece::Filled(kind, color) = (match ms.mv.promotion {
             Piece::Empty => self[ms.mv.dest],
             Piece::Filled(_, color) => Piece::pawn(color),
@@ -93,7 +193,8 @@ impl Board {
     }
 
     /// Make a move without checking to see if it is legal. A significant performance improvement
-    /// over the regular `make` method.
+    /// over the regular `make` method. Drops are made here as well, since a [Board] has no way of
+    /// knowing whether the side to move has the piece in hand.
     ///
     /// # Safety
     ///
@@ -103,18 +204,33 @@ impl Board {
     /// # Panics
     ///
     /// Panics if the [Piece] at the moves origin is Empty.
-    pub unsafe fn make_unchecked(
+    pub unsafe fn make_unchecked(&mut self, mv: Move) {
+        let ms = self.apply_unchecked(mv);
+        self.move_history.push(ms);
+    }
+
+    /// Makes a move without checking that it is legal or recording it in the history, returning
+    /// what is needed to unmake it
+    ///
+    /// # Safety
+    ///
+    /// The same as for [Board::make_unchecked].
+    pub(super) unsafe fn apply_unchecked(
         &mut self,
         mv @ Move {
             origin,
             dest,
             promotion,
         }: Move,
-    ) {
+    ) -> MoveState {
+        if mv.is_drop() {
+            return self.apply_drop(mv);
+        }
         let piece @ Piece::Filled(kind, color) = self[origin] else {
             panic!("Moving empty piece")
         };
 
+        let hash = self.hash;
         let ms = self.modify(|modifier| -> MoveState {
             let is_ep = matches!(modifier.board().ep_target, Some(e) if e == dest)
                 && kind == PieceKind::Pawn;
@@ -130,6 +246,7 @@ impl Board {
                 castle: modifier.board().castle,
                 halfmove: modifier.board().halfmove,
                 ep_target: modifier.board().ep_target,
+                hash,
             };
             if promotion != Piece::Empty {
                 modifier.put(promotion, dest);
@@ -148,15 +265,12 @@ impl Board {
             }
             modifier.toggle_color_to_move();
 
-            let is_dp = kind == PieceKind::Pawn && dest.index().abs_diff(origin.index()) == 16;
-            if is_dp {
-                let index = origin.index().max(dest.index()) - 8;
-                modifier.set_ep_target(Some(
-                    Square::try_from(index).expect("Bad Ep target calculation"),
-                ));
-            } else {
-                modifier.set_ep_target(None);
-            }
+            // The variant decides which pawn moves can be captured en passant
+            let ep_target = match kind {
+                PieceKind::Pawn => modifier.board().variant.ep_target_after(origin, dest),
+                _ => None,
+            };
+            modifier.set_ep_target(ep_target);
 
             // Update castling
             match kind {
@@ -188,15 +302,41 @@ impl Board {
         });
 
         // Updating metadata
-        self.move_history.push(ms);
         if piece.is_color(Color::Black) {
             self.fullmove += 1
         }
-        if ms.capture == Piece::Empty && piece.is_kind(PieceKind::Pawn) {
+        if ms.capture == Piece::Empty && !piece.is_kind(PieceKind::Pawn) {
             self.halfmove += 1;
         } else {
             self.halfmove = 0;
         }
+        ms
+    }
+
+    #[inline]
+    fn apply_drop(&mut self, mv: Move) -> MoveState {
+        let hash = self.hash;
+        let ms = self.modify(|modifier| -> MoveState {
+            let move_state = MoveState {
+                mv,
+                capture: Piece::Empty,
+                castle: modifier.board().castle,
+                halfmove: modifier.board().halfmove,
+                ep_target: modifier.board().ep_target,
+                hash,
+            };
+            modifier.put(mv.promotion, mv.dest);
+            modifier.toggle_color_to_move();
+            modifier.set_ep_target(None);
+            move_state
+        });
+
+        if mv.promotion.is_color(Color::Black) {
+            self.fullmove += 1;
+        }
+        // Adding material can't be undone, like a capture
+        self.halfmove = 0;
+        ms
     }
 }
 
@@ -235,6 +375,30 @@ mod tests {
         assert!(board.is_valid());
     }
 
+    #[test]
+    fn test_null_move() {
+        let fen = "4k3/8/8/8/1b6/8/3P4/4K3 b - - 3 10";
+        let mut board = Board::from_fen(fen).unwrap();
+        let hash = board.hash();
+
+        board.make_null().unwrap();
+        // The pawn is pinned once it is white's turn
+        assert_eq!(board.to_fen(), "4k3/8/8/8/1b6/8/3P4/4K3 w - - 4 11");
+        assert!(board.pins().contains("d2".parse().unwrap()));
+        assert_ne!(board.hash(), hash);
+
+        board.make("e1f2".parse().unwrap()).unwrap();
+        board.make("b4c5".parse().unwrap()).unwrap();
+        assert!(board.make_null().is_err());
+        board.unmake();
+        board.unmake();
+        board.unmake_null();
+        assert_eq!(board.to_fen(), fen);
+        assert_eq!(board.hash(), hash);
+        assert_eq!(board.to_san(Move::NULL), "--");
+        assert_eq!(Move::from_str("0000").unwrap(), Move::NULL);
+    }
+
     #[test]
     fn test_move_sequence() {
         let fen = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8";
@@ -2,6 +2,8 @@ use super::*;
 
 pub struct Modifier<'a> {
     pub(super) board: &'a mut Board,
+    /// The en passant target whose key was in the hash before the changes
+    pub(super) hashed_ep_target: Option<Square>,
 }
 
 impl<'a> Modifier<'a> {
@@ -60,13 +62,9 @@ impl<'a> Modifier<'a> {
 
     #[inline(always)]
     pub fn set_ep_target(&mut self, target: Option<Square>) {
-        if let Some(sqr) = self.board.ep_target {
-            hash::toggle_ep_hash(self.board, sqr);
-        }
+        // Whether the target is hashed depends on the finished position, so it is hashed in
+        // `complete`
         self.board.ep_target = target;
-        if let Some(sqr) = target {
-            hash::toggle_ep_hash(self.board, sqr);
-        }
     }
 
     #[inline(always)]
@@ -77,5 +75,11 @@ impl<'a> Modifier<'a> {
     #[inline(always)]
     pub(super) fn complete(&mut self) {
         self.board.update_position();
+        if let Some(sqr) = self.hashed_ep_target {
+            hash::toggle_ep_hash(self.board, sqr);
+        }
+        if let Some(sqr) = hash::hashed_ep_target(self.board) {
+            hash::toggle_ep_hash(self.board, sqr);
+        }
     }
 }
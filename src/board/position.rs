@@ -0,0 +1,288 @@
+use std::{
+    fmt::{self, Display},
+    hash::Hash,
+    ops,
+    sync::Arc,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{hash::StoredHash, Board};
+use crate::{
+    Bitboard, BoardError, Castle, Check, Color, Move, Piece, Square, Variant, ZobristHash,
+    ZobristKeys,
+};
+
+/// A snapshot of a [Board] without its move history, which is cheap to clone and send between
+/// threads. Moves are made by copying: [Position::play] returns the position after the move and
+/// leaves the original as it was.
+///
+/// A position can't take moves back or detect repetitions, since both need the history. Convert
+/// it to a [Board] for those. It shares the [ZobristKeys] of the board it came from, and is
+/// serialized with them like a board is.
+///
+/// Moves are made and generated by a [Board] copied from the position on the stack, so the
+/// move generation isn't duplicated. Its move history is left empty, so nothing is allocated
+/// on the heap beyond the [Vec] that [Position::legal_moves] returns.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{Board, Position};
+/// let start = Position::default();
+/// let after = start.play("e2e4".parse()?)?.play("c7c5".parse()?)?;
+///
+/// assert_eq!(start, Board::default().position());
+/// assert_eq!(after.to_fen(), "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2");
+///
+/// let board = Board::from(after.clone());
+/// assert_eq!(board.legal_moves().len(), after.legal_moves().len());
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Position {
+    bitboards: [Bitboard; 13],
+    color_bitboards: [Bitboard; 2],
+    attacks: Bitboard,
+    pins: Bitboard,
+    check: Check,
+    check_squares: [Bitboard; 6],
+    discovered_blockers: Bitboard,
+    color_to_move: Color,
+    #[cfg_attr(feature = "serde", serde(with = "serde_arrays"))]
+    pieces: [Piece; 64],
+    castle: [Castle; 2],
+    ep_target: Option<Square>,
+    variant: Variant,
+    halfmove: u32,
+    fullmove: u32,
+    hash: StoredHash,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default = "super::hash::default_keys",
+            with = "super::hash::serde_keys"
+        )
+    )]
+    keys: Arc<ZobristKeys>,
+}
+
+impl Position {
+    /// Returns the position after a move, checking that it is legal
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError::Move] saying why the move is not legal
+    pub fn play(&self, mv: Move) -> Result<Self, BoardError> {
+        let mut board = Board::from(self.clone());
+        board.check_legal(mv)?;
+        // The move is legal, and the history stays empty
+        unsafe { board.apply_unchecked(mv) };
+        Ok(board.position())
+    }
+
+    /// Returns the position after a move without checking that it is legal
+    ///
+    /// # Safety
+    ///
+    /// The same as for [Board::make_unchecked].
+    pub unsafe fn play_unchecked(&self, mv: Move) -> Self {
+        let mut board = Board::from(self.clone());
+        board.apply_unchecked(mv);
+        board.position()
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        Board::from(self.clone()).legal_moves()
+    }
+
+    pub fn to_fen(&self) -> String {
+        Board::from(self.clone()).to_fen()
+    }
+
+    pub fn color_to_move(&self) -> Color {
+        self.color_to_move
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn check(&self) -> Check {
+        self.check
+    }
+
+    pub fn castle(&self, color: Color) -> Castle {
+        self.castle[color]
+    }
+
+    pub fn ep_target(&self) -> Option<Square> {
+        self.ep_target
+    }
+
+    /// The number of moves since the last capture or pawn move
+    pub fn halfmove(&self) -> u32 {
+        self.halfmove
+    }
+
+    /// The number of the current full move, starting at 1 and going up after Black moves
+    pub fn fullmove(&self) -> u32 {
+        self.fullmove
+    }
+
+    /// The Zobrist hash, the same as [Board::hash] for the board it came from
+    pub fn hash(&self) -> ZobristHash {
+        self.hash as ZobristHash
+    }
+
+    /// The 128-bit Zobrist hash, the same as [Board::hash128] for the board it came from
+    #[cfg(feature = "zobrist128")]
+    pub fn hash128(&self) -> crate::ZobristHash128 {
+        self.hash
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Board::default().position()
+    }
+}
+
+impl Hash for Position {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_fen())
+    }
+}
+
+impl ops::Index<Piece> for Position {
+    type Output = Bitboard;
+
+    fn index(&self, index: Piece) -> &Self::Output {
+        &self.bitboards[index]
+    }
+}
+
+impl ops::Index<Square> for Position {
+    type Output = Piece;
+
+    fn index(&self, index: Square) -> &Self::Output {
+        &self.pieces[index]
+    }
+}
+
+impl ops::Index<Color> for Position {
+    type Output = Bitboard;
+
+    fn index(&self, index: Color) -> &Self::Output {
+        &self.color_bitboards[index]
+    }
+}
+
+impl Board {
+    /// Takes a [Position] snapshot of the board, leaving out the move history
+    pub fn position(&self) -> Position {
+        Position {
+            bitboards: self.bitboards,
+            color_bitboards: self.color_bitboards,
+            attacks: self.attacks,
+            pins: self.pins,
+            check: self.check,
+            check_squares: self.check_squares,
+            discovered_blockers: self.discovered_blockers,
+            color_to_move: self.color_to_move,
+            pieces: self.pieces,
+            castle: self.castle,
+            ep_target: self.ep_target,
+            variant: self.variant,
+            halfmove: self.halfmove,
+            fullmove: self.fullmove,
+            hash: self.hash,
+            keys: self.keys.clone(),
+        }
+    }
+}
+
+impl From<&Board> for Position {
+    fn from(board: &Board) -> Self {
+        board.position()
+    }
+}
+
+impl From<Position> for Board {
+    /// Makes a board from a position, with no moves to unmake
+    fn from(position: Position) -> Self {
+        Board {
+            bitboards: position.bitboards,
+            color_bitboards: position.color_bitboards,
+            attacks: position.attacks,
+            pins: position.pins,
+            check: position.check,
+            check_squares: position.check_squares,
+            discovered_blockers: position.discovered_blockers,
+            color_to_move: position.color_to_move,
+            pieces: position.pieces,
+            castle: position.castle,
+            ep_target: position.ep_target,
+            variant: position.variant,
+            halfmove: position.halfmove,
+            fullmove: position.fullmove,
+            move_history: Vec::new(),
+            hash: position.hash,
+            keys: position.keys,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread};
+
+    use crate::{Board, BoardBuilder, Position, ZobristKeys};
+
+    #[test]
+    fn test_play() {
+        let mut board = Board::default();
+        let mut position = Position::default();
+        for mv in [
+            "e2e4", "d7d5", "e4d5", "g8f6", "f1b5", "c7c6", "d5c6", "d8a5",
+        ] {
+            let mv = mv.parse().unwrap();
+            board.make(mv).unwrap();
+            position = position.play(mv).unwrap();
+        }
+        assert_eq!(position, board.position());
+        assert_eq!(position.hash(), board.hash());
+        assert_eq!(Board::from(position.clone()), board);
+
+        // Playing a move, legal or not, leaves the position it was played from as it was
+        let before = position.clone();
+        assert!(position.play("c6b7".parse().unwrap()).is_ok());
+        assert!(position.play("b7a8q".parse().unwrap()).is_err());
+        assert_eq!(position, before);
+    }
+
+    #[test]
+    fn test_send() {
+        // Positions own their keys, so they outlive the board and move to other threads
+        let keys = Arc::new(ZobristKeys::seeded([3; 32]));
+        let position = {
+            let board = BoardBuilder::default().zobrist_keys(keys).build().unwrap();
+            board.position()
+        };
+        let worker = position.clone();
+        let played = thread::spawn(move || worker.play("e2e4".parse().unwrap()).unwrap())
+            .join()
+            .unwrap();
+        let mut board = Board::from(position);
+        board.make("e2e4".parse().unwrap()).unwrap();
+        assert_eq!(played, board.position());
+        assert_eq!(played.hash(), board.hash());
+    }
+}
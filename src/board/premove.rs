@@ -0,0 +1,257 @@
+use super::Board;
+use crate::{squares, Color, Game, Move, MoveError, Piece, PieceKind};
+
+/// What happened to the first queued premove once it was the premover's turn
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PremoveOutcome {
+    /// Nothing is queued, or it is still the opponent's turn
+    Waiting,
+    /// The premove was legal and has been made
+    Played(Move),
+    /// The premove wasn't legal in the position the opponent left, so it and every premove
+    /// queued after it were dropped
+    Cancelled(Move, MoveError),
+}
+
+/// A chain of premoves queued by one player while their opponent is thinking.
+///
+/// Each premove is checked when it is queued against the position the premover would have if
+/// the opponent passed and the earlier premoves were all made. Only the way the piece moves is
+/// checked then, since the opponent's move can still change what is legal: pieces may be
+/// captured, squares may be emptied or filled, and the king may be put in check. So a piece may
+/// premove past other pieces or onto one of its own, as for a recapture, and a pawn may premove
+/// diagonally onto an empty square. Castling only needs the castling right. Once the
+/// opponent has moved, [PremoveQueue::play] checks the first premove again with the full rules
+/// and makes it, or cancels the whole chain.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{Board, Color, PremoveOutcome, PremoveQueue};
+/// let mut board = Board::default();
+/// board.make("e2e4".parse()?)?;
+///
+/// // While Black thinks, White queues Nf3 and Bc4
+/// let mut premoves = PremoveQueue::new(Color::White);
+/// premoves.push(&board, "g1f3".parse()?)?;
+/// premoves.push(&board, "f1c4".parse()?)?;
+///
+/// board.make("e7e5".parse()?)?;
+/// assert_eq!(premoves.play(&mut board), PremoveOutcome::Played("g1f3".parse()?));
+/// assert_eq!(premoves.play(&mut board), PremoveOutcome::Waiting);
+/// assert_eq!(premoves.moves(), ["f1c4".parse()?]);
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PremoveQueue {
+    color: Color,
+    moves: Vec<Move>,
+}
+
+impl PremoveQueue {
+    /// Creates an empty queue for the given player
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            moves: Vec::new(),
+        }
+    }
+
+    /// The player the premoves are for
+    pub fn color(&self) -> Color {
+        self.color
+    }
+
+    /// The queued premoves, in the order they will be played
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Cancels every queued premove
+    pub fn clear(&mut self) {
+        self.moves.clear();
+    }
+
+    /// The position the premover would have after the queued premoves if the opponent passed
+    /// every turn, as a GUI would show it
+    pub fn position(&self, board: &Board) -> Board {
+        let mut position = board.clone();
+        self.to_premover(&mut position);
+        for mv in &self.moves {
+            // Every queued move fits how its piece moves here and captures no king
+            unsafe { position.make_unchecked(*mv) };
+            self.to_premover(&mut position);
+        }
+        position
+    }
+
+    /// Queues a premove after the ones already queued.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [MoveError] if the piece can't move that way in the position after the queued
+    /// premoves, the promotion doesn't fit the move, or the move would land on a king.
+    pub fn push(&mut self, board: &Board, mv: Move) -> Result<(), MoveError> {
+        let position = self.position(board);
+        let piece = position[mv.origin];
+        if piece == Piece::Empty {
+            return Err(MoveError::NoPiece(mv.origin));
+        }
+        if !piece.is_color(self.color) {
+            return Err(MoveError::WrongColor(mv.origin));
+        }
+
+        let fits = position.movement(mv).is_some() || self.castles(&position, mv);
+        let promotion = match mv.promotion {
+            Piece::Empty => !position.is_promotion(mv.origin, mv.dest),
+            Piece::Filled(kind, color) => {
+                color == self.color
+                    && position.is_promotion(mv.origin, mv.dest)
+                    && position.variant.promotion_kinds().contains(&kind)
+            }
+        };
+        // A king can't be captured, and the premover's own king can't leave the square first
+        let king = position[mv.dest] == Piece::king(!self.color)
+            || position[mv.dest] == Piece::king(self.color) && position.variant.has_check();
+        if !fits || !promotion || king {
+            return Err(MoveError::IllegalDestination(mv));
+        }
+        self.moves.push(mv);
+        Ok(())
+    }
+
+    /// Whether a king move two squares from its starting square castles on a side the premover
+    /// still has the right to
+    fn castles(&self, position: &Board, mv: Move) -> bool {
+        let (start, king_side, queen_side) = match self.color {
+            Color::White => (squares::E1, squares::G1, squares::C1),
+            Color::Black => (squares::E8, squares::G8, squares::C8),
+        };
+        let castle = position.castle[self.color];
+        position[mv.origin].is_kind(PieceKind::King)
+            && position.variant.has_castling()
+            && mv.origin == start
+            && mv.promotion == Piece::Empty
+            && (mv.dest == king_side && castle.get_king_side()
+                || mv.dest == queen_side && castle.get_queen_side())
+    }
+
+    /// Makes the first premove on `board` once the opponent has moved, or cancels the chain if
+    /// it is no longer legal. Does nothing while it is the opponent's turn.
+    pub fn play(&mut self, board: &mut Board) -> PremoveOutcome {
+        let outcome = self.next(board);
+        if let PremoveOutcome::Played(mv) = outcome {
+            unsafe { board.make_unchecked(mv) };
+        }
+        outcome
+    }
+
+    /// Makes the first premove in `game`, as [PremoveQueue::play] does on a board
+    pub fn play_in_game(&mut self, game: &mut Game) -> PremoveOutcome {
+        let outcome = self.next(game.board());
+        if let PremoveOutcome::Played(mv) = outcome {
+            game.make(mv).expect("The premove was checked to be legal");
+        }
+        outcome
+    }
+
+    /// Takes the first premove if it is the premover's turn, checking it is legal
+    fn next(&mut self, board: &Board) -> PremoveOutcome {
+        if board.color_to_move != self.color || self.moves.is_empty() {
+            return PremoveOutcome::Waiting;
+        }
+        let mv = self.moves.remove(0);
+        match board.check_legal(mv) {
+            Ok(()) => PremoveOutcome::Played(mv),
+            Err(err) => {
+                self.moves.clear();
+                PremoveOutcome::Cancelled(mv, err)
+            }
+        }
+    }
+
+    /// Gives the move to the premover, without an en passant capture the opponent left them
+    fn to_premover(&self, position: &mut Board) {
+        position.modify(|m| {
+            if m.board.color_to_move != self.color {
+                m.toggle_color_to_move();
+            }
+            m.set_ep_target(None);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Color, MoveError, PremoveOutcome, PremoveQueue};
+
+    #[test]
+    fn test_premoves() {
+        let board = Board::from_fen("r3k3/8/8/3p4/8/8/8/R3K3 b Q - 0 1").unwrap();
+        let mut premoves = PremoveQueue::new(Color::White);
+
+        // The rook can premove from the square it will have moved to
+        premoves.push(&board, "a1a5".parse().unwrap()).unwrap();
+        premoves.push(&board, "a5d5".parse().unwrap()).unwrap();
+        assert_eq!(
+            premoves.push(&board, "d5b7".parse().unwrap()),
+            Err(MoveError::IllegalDestination("d5b7".parse().unwrap()))
+        );
+        assert_eq!(
+            premoves.push(&board, "e8e7".parse().unwrap()),
+            Err(MoveError::WrongColor("e8".parse().unwrap()))
+        );
+        assert_eq!(
+            premoves.position(&board).to_fen(),
+            "r3k3/8/8/3R4/8/8/8/4K3 w - - 0 1"
+        );
+
+        let mut played = board.clone();
+        let mut queue = premoves.clone();
+        assert_eq!(queue.play(&mut played), PremoveOutcome::Waiting);
+        played.make("d5d4".parse().unwrap()).unwrap();
+        assert_eq!(
+            queue.play(&mut played),
+            PremoveOutcome::Played("a1a5".parse().unwrap())
+        );
+        assert_eq!(queue.moves(), ["a5d5".parse().unwrap()]);
+
+        // Black blocks the a-file, which cancels the whole chain
+        let mut blocked = board.clone();
+        blocked.make("a8a3".parse().unwrap()).unwrap();
+        assert_eq!(
+            premoves.play(&mut blocked),
+            PremoveOutcome::Cancelled(
+                "a1a5".parse().unwrap(),
+                MoveError::Blocked("a1a5".parse().unwrap(), "a3".parse().unwrap())
+            )
+        );
+        assert!(premoves.is_empty());
+
+        // The pawn premoves a recapture onto its own bishop, and may premove past pieces or
+        // diagonally onto an empty square
+        let board = Board::from_fen("3qk3/8/8/3B4/4P3/8/8/4K3 b - - 0 1").unwrap();
+        let mut premoves = PremoveQueue::new(Color::White);
+        premoves.push(&board, "e4d5".parse().unwrap()).unwrap();
+        let mut other = PremoveQueue::new(Color::White);
+        other.push(&board, "d5h1".parse().unwrap()).unwrap();
+        other.push(&board, "e4f5".parse().unwrap()).unwrap();
+        for mv in ["e1e8", "f5f7", "f5g5"] {
+            assert_eq!(
+                other.push(&board, mv.parse().unwrap()),
+                Err(MoveError::IllegalDestination(mv.parse().unwrap()))
+            );
+        }
+
+        let mut played = board.clone();
+        played.make("d8d5".parse().unwrap()).unwrap();
+        assert_eq!(
+            premoves.play(&mut played),
+            PremoveOutcome::Played("e4d5".parse().unwrap())
+        );
+        assert_eq!(played.to_fen(), "4k3/8/8/3P4/8/8/8/4K3 b - - 0 2");
+    }
+}
@@ -0,0 +1,138 @@
+use super::hash::StoredHash;
+use crate::Board;
+
+impl Board {
+    /// Returns how many times the current position has occurred, counting this occurrence. Only
+    /// positions since the last capture, pawn move or null move are looked at, since no earlier
+    /// position can come up again, so this is cheap enough to call at every node of a search.
+    ///
+    /// Positions are compared by their hash, which includes the side to move, castling rights and
+    /// the en passant target when a pawn can legally capture on it. As in FIDE rules, a double
+    /// push that can't be captured en passant doesn't make the position differ.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let mut board = Board::default();
+    /// for _ in 0..2 {
+    ///     for mv in ["g1f3", "g8f6", "f3g1", "f6g8"] {
+    ///         board.make(mv.parse()?)?;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(board.repetition_count(), 3);
+    /// assert!(board.is_repetition(3));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn repetition_count(&self) -> usize {
+        self.earlier_positions()
+            .filter(|hash| *hash == self.hash)
+            .count()
+            + 1
+    }
+
+    /// Returns true if the current position has occurred at least `n` times, counting this
+    /// occurrence. Stops looking as soon as it has found enough.
+    pub fn is_repetition(&self, n: usize) -> bool {
+        n <= 1
+            || self
+                .earlier_positions()
+                .filter(|hash| *hash == self.hash)
+                .nth(n - 2)
+                .is_some()
+    }
+
+    /// The hashes of earlier positions with the same side to move that could repeat the current
+    /// one, most recent first
+    fn earlier_positions(&self) -> impl Iterator<Item = StoredHash> + '_ {
+        self.move_history
+            .iter()
+            .rev()
+            .take(self.halfmove as usize)
+            .take_while(|ms| !ms.mv.is_null())
+            .skip(1)
+            .step_by(2)
+            .map(|ms| ms.hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, DrawReason};
+
+    #[test]
+    fn test_repetition() {
+        let mut board = Board::default();
+        let shuffle = ["b1c3", "b8c6", "c3b1", "c6b8"];
+        for mv in shuffle {
+            board.make(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(board.repetition_count(), 2);
+        assert!(!board.is_repetition(3));
+
+        // A pawn move means no earlier position can repeat
+        board.make("e2e3".parse().unwrap()).unwrap();
+        board.make("e7e6".parse().unwrap()).unwrap();
+        for mv in shuffle {
+            board.make(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(board.repetition_count(), 2);
+
+        // Passing the turn doesn't repeat a position
+        board.make_null().unwrap();
+        board.make_null().unwrap();
+        assert_eq!(board.repetition_count(), 1);
+        board.unmake_null();
+        board.unmake_null();
+
+        for mv in shuffle {
+            board.make(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(board.repetition_count(), 3);
+        assert!(board.is_repetition(3));
+        assert!(!board.is_repetition(4));
+        board.unmake();
+        assert_eq!(board.repetition_count(), 2);
+    }
+
+    #[test]
+    fn test_en_passant() {
+        // The position after 1. e4 repeats, since no black pawn can capture en passant
+        let mut board = Board::default();
+        for mv in [
+            "e2e4", "g8f6", "g1f3", "f6g8", "f3g1", "g8f6", "g1f3", "f6g8", "f3g1",
+        ] {
+            board.make(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(board.repetition_count(), 3);
+        assert_eq!(
+            board.claimable_draw(),
+            Some(DrawReason::ThreefoldRepetition)
+        );
+
+        // Once the chance to capture en passant has passed, the position is a new one
+        let mut board =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/8/3p4/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        for mv in [
+            "e2e4", "g8f6", "g1f3", "f6g8", "f3g1", "g8f6", "g1f3", "f6g8", "f3g1",
+        ] {
+            board.make(mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(board.repetition_count(), 2);
+    }
+
+    #[test]
+    fn test_halfmove_clock() {
+        let mut board = Board::default();
+        board.make("g1f3".parse().unwrap()).unwrap();
+        assert!(board.to_fen().ends_with(" 1 1"));
+        board.make("e7e5".parse().unwrap()).unwrap();
+        assert!(board.to_fen().ends_with(" 0 2"));
+        board.make("f3e5".parse().unwrap()).unwrap();
+        assert!(board.to_fen().ends_with(" 0 2"));
+        board.make("b8c6".parse().unwrap()).unwrap();
+        assert!(board.to_fen().ends_with(" 1 3"));
+        board.unmake();
+        assert!(board.to_fen().ends_with(" 0 2"));
+    }
+}
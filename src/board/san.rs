@@ -0,0 +1,243 @@
+use regex::Regex;
+
+use crate::{Board, BoardError, Move, NotationError, Piece, PieceKind, Square};
+
+impl Board {
+    /// Writes a legal move in Standard Algebraic Notation (SAN), including the `+` or `#` suffix
+    /// when the move gives check or checkmate. Drops are written as `N@f3` and null moves as `--`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let board = Board::default();
+    ///
+    /// assert_eq!(board.to_san("g1f3".parse()?), "Nf3");
+    /// assert_eq!(board.to_san("e2e4".parse()?), "e4");
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn to_san(&self, mv: Move) -> String {
+        if mv.is_null() {
+            return "--".to_owned();
+        }
+        let mut output = if mv.is_drop() {
+            let kind = mv.promotion.kind().expect("Drops place a piece");
+            format!("{}@{}", kind.to_string().to_uppercase(), mv.dest)
+        } else {
+            self.san_body(mv)
+        };
+
+        if self.gives_check(mv) {
+            // Only a checking move needs to be made to see whether it mates
+            let mut after = self.clone();
+            unsafe {
+                after.make_unchecked(mv);
+            }
+            if after.legal_moves().is_empty() {
+                output.push('#');
+            } else {
+                output.push('+');
+            }
+        }
+        output
+    }
+
+    fn san_body(&self, mv: Move) -> String {
+        let Piece::Filled(kind, _) = self[mv.origin] else {
+            panic!("Moving empty piece")
+        };
+
+        if kind == PieceKind::King && mv.origin.index().abs_diff(mv.dest.index()) == 2 {
+            return if mv.dest.index() < mv.origin.index() {
+                "O-O".to_owned()
+            } else {
+                "O-O-O".to_owned()
+            };
+        }
+
+        let is_capture = self[mv.dest] != Piece::Empty
+            || kind == PieceKind::Pawn && Some(mv.dest) == self.ep_target();
+        let origin = mv.origin.to_string();
+        let mut output = String::new();
+
+        if kind == PieceKind::Pawn {
+            if is_capture {
+                output.push_str(&origin[..1]);
+            }
+        } else {
+            output += &kind.to_string().to_uppercase();
+            let others: Vec<Square> = self
+                .legal_moves()
+                .into_iter()
+                .filter(|other| {
+                    other.dest == mv.dest
+                        && other.origin != mv.origin
+                        && self[other.origin].is_kind(kind)
+                })
+                .map(|other| other.origin)
+                .collect();
+            if !others.is_empty() {
+                if others.iter().all(|sq| sq.file() != mv.origin.file()) {
+                    output.push_str(&origin[..1]);
+                } else if others.iter().all(|sq| sq.rank() != mv.origin.rank()) {
+                    output.push_str(&origin[1..]);
+                } else {
+                    output += &origin;
+                }
+            }
+        }
+
+        if is_capture {
+            output.push('x');
+        }
+        output += &mv.dest.to_string();
+
+        if let Some(promo) = mv.promotion.kind() {
+            output += &format!("={}", promo.to_string().to_uppercase());
+        }
+        output
+    }
+
+    /// Finds the legal move described by a string in Standard Algebraic Notation (SAN). Check
+    /// and annotation suffixes like `+`, `#`, `!` and `?` are ignored.
+    ///
+    /// Drops like `N@f3` are returned for the side to move without checking whether they are
+    /// legal, since a [Board] does not know what pieces a player has in hand. `--` is read as
+    /// [Move::NULL].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if the string is not valid SAN, or if it matches no legal move or
+    /// more than one.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{Board, Move};
+    /// let board = Board::default();
+    ///
+    /// assert_eq!(board.parse_san("Nf3")?, "g1f3".parse::<Move>()?);
+    /// assert!(board.parse_san("Nd4").is_err());
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn parse_san(&self, san: &str) -> Result<Move, BoardError> {
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+        let invalid = || BoardError::from(NotationError::InvalidSan(san.to_owned()));
+        let color = self.color_to_move();
+
+        let candidates: Vec<Move> = match san {
+            "--" => return Ok(Move::NULL),
+            "O-O" | "0-0" | "O-O-O" | "0-0-0" => {
+                let king_side = san.len() == 3;
+                self.legal_moves()
+                    .into_iter()
+                    .filter(|mv| {
+                        self[mv.origin].is_kind(PieceKind::King)
+                            && mv.origin.index().abs_diff(mv.dest.index()) == 2
+                            && (mv.dest.index() < mv.origin.index()) == king_side
+                    })
+                    .collect()
+            }
+            _ if san.contains('@') => {
+                let re = Regex::new(r"^([PNBRQ])?@([a-h][1-8])$").expect("Invalid Regex for drops");
+                let caps = re.captures(san).ok_or_else(invalid)?;
+                let kind = match caps.get(1) {
+                    Some(p) => p.as_str().to_lowercase().parse::<Piece>()?.kind(),
+                    None => Some(PieceKind::Pawn),
+                }
+                .ok_or_else(invalid)?;
+                let dest: Square = caps[2].parse()?;
+                return Ok(Move::drop(Piece::Filled(kind, color), dest));
+            }
+            _ => {
+                let re = Regex::new(r"^([NBRQK])?([a-h])?([1-8])?x?([a-h][1-8])(?:=?([NBRQK]))?$")
+                    .expect("Invalid Regex for SAN");
+                let caps = re.captures(san).ok_or_else(invalid)?;
+                let kind = match caps.get(1) {
+                    Some(p) => p.as_str().to_lowercase().parse::<Piece>()?.kind(),
+                    None => Some(PieceKind::Pawn),
+                }
+                .ok_or_else(invalid)?;
+                let dest: Square = caps[4].parse()?;
+                let promotion = match caps.get(5) {
+                    Some(p) => {
+                        let promo = p.as_str().to_lowercase().parse::<Piece>()?;
+                        if kind != PieceKind::Pawn
+                            || (promo.is_kind(PieceKind::King)
+                                && !self.variant().promotion_kinds().contains(&PieceKind::King))
+                        {
+                            return Err(invalid());
+                        }
+                        Piece::Filled(promo.kind().ok_or_else(invalid)?, color)
+                    }
+                    None => Piece::Empty,
+                };
+                let file = caps.get(2).map(|f| f.as_str().as_bytes()[0]);
+                let rank = caps.get(3).map(|r| r.as_str().as_bytes()[0]);
+
+                self.legal_moves()
+                    .into_iter()
+                    .filter(|mv| {
+                        let origin = mv.origin.to_string().into_bytes();
+                        self[mv.origin].is_kind(kind)
+                            && mv.dest == dest
+                            && mv.promotion == promotion
+                            && file.is_none_or(|f| origin[0] == f)
+                            && rank.is_none_or(|r| origin[1] == r)
+                    })
+                    .collect()
+            }
+        };
+
+        match candidates[..] {
+            [mv] => Ok(mv),
+            [] => Err(NotationError::NoMatchingMove(san.to_owned()).into()),
+            _ => Err(NotationError::AmbiguousSan(san.to_owned()).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Move};
+
+    #[test]
+    fn test_san_round_trip() {
+        let fens = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ];
+        for fen in fens {
+            let board = Board::from_fen(fen).unwrap();
+            for mv in board.legal_moves() {
+                let san = board.to_san(mv);
+                assert_eq!(board.parse_san(&san).unwrap(), mv, "{san} in {fen}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_san_notation() {
+        let board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let san = |mv: &str| board.to_san(mv.parse::<Move>().unwrap());
+        assert_eq!(san("e1g1"), "O-O");
+        assert_eq!(san("e1c1"), "O-O-O");
+        assert_eq!(san("e5f7"), "Nxf7");
+        assert_eq!(san("c3b1"), "Nb1");
+        assert_eq!(san("d5e6"), "dxe6");
+        assert_eq!(san("f3f7"), "Qxf7+");
+
+        let board = Board::from_fen("4k3/8/8/1N6/8/8/8/1N1NK3 w - - 0 1").unwrap();
+        let san = |mv: &str| board.to_san(mv.parse::<Move>().unwrap());
+        assert_eq!(san("d1c3"), "Ndc3");
+        assert_eq!(san("b5c3"), "N5c3");
+        assert_eq!(san("b1c3"), "Nb1c3");
+
+        let board = Board::from_fen("7k/8/8/8/8/8/8/K5R1 w - - 0 1").unwrap();
+        assert_eq!(board.to_san("g1g8".parse().unwrap()), "Rg8+");
+        let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 0 1").unwrap();
+        assert_eq!(board.to_san("a1a8".parse().unwrap()), "Ra8#");
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{Bitboard, Board, Color, Dir, Move, Piece, PieceKind, EMPTY};
+
+/// Material values in centipawns used to score exchanges. The king is worth more than every
+/// other piece combined, so capturing it is never a losing trade.
+fn value(kind: PieceKind) -> i32 {
+    match kind {
+        PieceKind::Pawn => 100,
+        PieceKind::Knight => 300,
+        PieceKind::Bishop => 300,
+        PieceKind::Rook => 500,
+        PieceKind::Queen => 900,
+        PieceKind::King => 20000,
+    }
+}
+
+const EXCHANGE_ORDER: [PieceKind; 6] = [
+    PieceKind::Pawn,
+    PieceKind::Knight,
+    PieceKind::Bishop,
+    PieceKind::Rook,
+    PieceKind::Queen,
+    PieceKind::King,
+];
+
+impl Board {
+    /// Static Exchange Evaluation. Plays out every capture on the destination square of `mv`,
+    /// with each side recapturing with its least valuable attacker and stopping once
+    /// recapturing would lose material, and returns the material the side to move gains in
+    /// centipawns. Sliders revealed behind other attackers join the exchange, but pins are not
+    /// taken into account.
+    ///
+    /// The move is expected to be legal. A quiet move scores 0 unless the piece can be won.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let board = Board::from_fen("1k1r4/1pp4p/p7/4p3/8/P5P1/1PP4P/2K1R3 w - - 0 1")?;
+    /// // The pawn on e5 is only defended once
+    /// assert_eq!(board.see("e1e5".parse()?), 100);
+    ///
+    /// let board = Board::from_fen("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1")?;
+    /// // Taking the pawn with the knight loses it to the defenders behind
+    /// assert_eq!(board.see("d3e5".parse()?), -200);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn see(&self, mv: Move) -> i32 {
+        let Some(mut on_square) = self[mv.origin].kind() else {
+            return 0;
+        };
+        let is_ep = on_square == PieceKind::Pawn && Some(mv.dest) == self.ep_target();
+
+        let mut gain = [0; 32];
+        gain[0] = match self[mv.dest].kind() {
+            Some(kind) => value(kind),
+            None if is_ep => value(PieceKind::Pawn),
+            None => 0,
+        };
+        if let Some(promotion) = mv.promotion.kind() {
+            gain[0] += value(promotion) - value(PieceKind::Pawn);
+            on_square = promotion;
+        }
+
+        let mut occupancy = !self[Piece::Empty] ^ mv.origin.into();
+        if is_ep {
+            occupancy ^= self.ep_victim();
+        }
+        let mut color = !self.color_to_move;
+        let mut depth = 0;
+
+        loop {
+            let attackers = self.attackers_to(mv.dest, occupancy) & self[color];
+            let Some((kind, from)) = EXCHANGE_ORDER.iter().find_map(|kind| {
+                (attackers & self[Piece::Filled(*kind, color)])
+                    .first_square()
+                    .map(|sq| (*kind, sq))
+            }) else {
+                break;
+            };
+            // The king may only recapture if nothing can take it back
+            if kind == PieceKind::King
+                && !(self.attackers_to(mv.dest, occupancy) & self[!color]).is_empty()
+            {
+                break;
+            }
+
+            depth += 1;
+            gain[depth] = value(on_square) - gain[depth - 1];
+            // The capture loses material whether or not it is answered, so it is never made
+            if (-gain[depth - 1]).max(gain[depth]) < 0 {
+                depth -= 1;
+                break;
+            }
+            if depth == gain.len() - 1 {
+                break;
+            }
+            occupancy ^= from.into();
+            on_square = kind;
+            color = !color;
+        }
+
+        while depth > 0 {
+            gain[depth - 1] = -(-gain[depth - 1]).max(gain[depth]);
+            depth -= 1;
+        }
+        gain[0]
+    }
+
+    /// Returns true if the [Static Exchange Evaluation](Board::see) of `mv` is at least
+    /// `threshold`. `see_ge(mv, 0)` is the usual test for whether a capture loses material.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Board;
+    /// let board = Board::from_fen("1k1r3q/1ppn3p/p4b2/4p3/8/P2N2P1/1PP1R1BP/2K1Q3 w - - 0 1")?;
+    /// assert!(!board.see_ge("d3e5".parse()?, 0));
+    /// assert!(board.see_ge("d3e5".parse()?, -200));
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn see_ge(&self, mv: Move, threshold: i32) -> bool {
+        self.see(mv) >= threshold
+    }
+
+    /// The square of the pawn that would be captured en passant
+    pub(super) fn ep_victim(&self) -> Bitboard {
+        match (self.ep_target, self.color_to_move) {
+            (Some(target), Color::White) => Bitboard::from(target) << Dir::South,
+            (Some(target), Color::Black) => Bitboard::from(target) << Dir::North,
+            (None, _) => EMPTY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Board;
+
+    #[test]
+    fn test_see() {
+        let cases = [
+            // Undefended piece
+            ("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1", "d1d5", 900),
+            // Pawn defended by a pawn
+            ("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", "d1d5", -800),
+            // The queen behind the rook joins in once the rook has captured
+            ("3rk3/8/8/3p4/8/8/3R4/3QK3 w - - 0 1", "d2d5", 100),
+            // Equal trade
+            ("4k3/8/5n2/3n4/8/4N3/8/4K3 w - - 0 1", "e3d5", 0),
+            // En passant
+            ("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6", 100),
+            // Quiet move onto a defended square
+            ("4k3/4p3/8/8/8/8/8/3QK3 w - - 0 1", "d1d6", -900),
+            // Promotion with a capture that can't be answered
+            ("1n2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8q", 1100),
+            // The king recaptures only when nothing else can take back
+            ("8/8/4k3/3p4/8/8/8/3RK3 w - - 0 1", "d1d5", -400),
+            ("8/8/4k3/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5", 100),
+        ];
+        for (fen, mv, expected) in cases {
+            let board = Board::from_fen(fen).unwrap();
+            assert_eq!(board.see(mv.parse().unwrap()), expected, "{mv} in {fen}");
+        }
+    }
+}
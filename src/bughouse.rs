@@ -0,0 +1,589 @@
+use std::{fmt, ops, time::Duration};
+
+use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::Tags, move_gen, Bitboard, Board, BoardError, Clock, Color, Move, MoveError,
+    NotationError, Outcome, Piece, PieceKind, TimeControl, WinReason, EMPTY,
+};
+
+/// One of the two boards in a [Bughouse] match. White on board A and black on board B are
+/// partners, as are black on board A and white on board B.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BoardId {
+    A,
+    B,
+}
+
+impl ops::Not for BoardId {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        match self {
+            BoardId::A => BoardId::B,
+            BoardId::B => BoardId::A,
+        }
+    }
+}
+
+impl<T> ops::Index<BoardId> for [T; 2] {
+    type Output = T;
+
+    fn index(&self, index: BoardId) -> &Self::Output {
+        &self[index as usize]
+    }
+}
+impl<T> ops::IndexMut<BoardId> for [T; 2] {
+    fn index_mut(&mut self, index: BoardId) -> &mut Self::Output {
+        &mut self[index as usize]
+    }
+}
+
+/// The pieces a player has in hand and may drop onto their board.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Pocket([u8; 6]);
+
+impl Pocket {
+    /// Returns how many pieces of the given kind are in the pocket
+    pub fn count(&self, kind: PieceKind) -> u8 {
+        self.0[kind as usize]
+    }
+
+    /// Returns every kind of piece there is at least one of in the pocket
+    pub fn kinds(&self) -> Vec<PieceKind> {
+        [
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+            PieceKind::Pawn,
+        ]
+        .into_iter()
+        .filter(|kind| self.count(*kind) > 0)
+        .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|c| *c == 0)
+    }
+
+    fn add(&mut self, kind: PieceKind) {
+        self.0[kind as usize] += 1;
+    }
+
+    fn remove(&mut self, kind: PieceKind) {
+        self.0[kind as usize] -= 1;
+    }
+}
+
+impl fmt::Display for Pocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for kind in self.kinds() {
+            for _ in 0..self.count(kind) {
+                write!(f, "{}", kind.to_string().to_uppercase())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The end of a [Bughouse] match, which is decided by the first board to finish.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BughouseOutcome {
+    pub board: BoardId,
+    pub outcome: Outcome,
+}
+
+impl BughouseOutcome {
+    /// Returns the winning team, identified by the [Color] it plays on board A, or `None` if
+    /// the match was drawn.
+    pub fn winning_team(&self) -> Option<Color> {
+        let winner = self.outcome.winner()?;
+        Some(match self.board {
+            BoardId::A => winner,
+            BoardId::B => !winner,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct BughouseMove {
+    board: BoardId,
+    mv: Move,
+    san: String,
+    transfer: Option<PieceKind>,
+    promoted: Bitboard,
+}
+
+/// Two linked [Board]s where every captured piece goes to the capturer's partner, who may drop
+/// it onto the other board as their move.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{BoardId, Bughouse, Color, PieceKind};
+/// let mut bughouse = Bughouse::new();
+/// for mv in ["e2e4", "d7d5", "e4d5"] {
+///     bughouse.make(BoardId::A, mv.parse()?)?;
+/// }
+///
+/// // White captured a pawn on board A, so black on board B can drop it
+/// assert_eq!(bughouse.pocket(BoardId::B, Color::Black).count(PieceKind::Pawn), 1);
+///
+/// bughouse.make(BoardId::B, "g1f3".parse()?)?;
+/// bughouse.make(BoardId::B, "P@e4".parse()?)?;
+/// assert_eq!(bughouse[BoardId::B].to_fen(), "rnbqkbnr/pppppppp/8/8/4p3/5N2/PPPPPPPP/RNBQKB1R w KQkq - 0 2");
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Bughouse {
+    boards: [Board; 2],
+    pockets: [[Pocket; 2]; 2],
+    /// Pieces on each board that were promoted, which return to a pocket as pawns
+    promoted: [Bitboard; 2],
+    clocks: Option<[Clock; 2]>,
+    history: Vec<BughouseMove>,
+    tags: Tags,
+}
+
+impl Default for Bughouse {
+    fn default() -> Self {
+        Self {
+            boards: [Board::default(), Board::default()],
+            pockets: [[Pocket::default(); 2]; 2],
+            promoted: [EMPTY; 2],
+            clocks: None,
+            history: Vec::new(),
+            tags: Tags::default(),
+        }
+    }
+}
+
+impl ops::Index<BoardId> for Bughouse {
+    type Output = Board;
+
+    fn index(&self, index: BoardId) -> &Self::Output {
+        &self.boards[index]
+    }
+}
+
+impl Bughouse {
+    /// Creates an untimed match with both boards in the starting position
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a match where every player has the given amount of time for the whole game
+    pub fn with_time(initial: Duration) -> Self {
+        Self::with_time_control(TimeControl::sudden_death(initial))
+    }
+
+    /// Creates a match where every player plays under the given [TimeControl]
+    pub fn with_time_control(control: TimeControl) -> Self {
+        let clock = Clock::new(control);
+        Self {
+            clocks: Some([clock.clone(), clock]),
+            ..Self::default()
+        }
+    }
+
+    pub fn board(&self, board: BoardId) -> &Board {
+        &self.boards[board]
+    }
+
+    /// Returns the pieces the given [Color] has in hand on the given board
+    pub fn pocket(&self, board: BoardId, color: Color) -> Pocket {
+        self.pockets[board][color]
+    }
+
+    /// Returns all legal moves, including drops, for the side to move on the given board
+    pub fn legal_moves(&self, board: BoardId) -> Vec<Move> {
+        let mut mvs = self.boards[board].legal_moves();
+        mvs.append(&mut self.drops(board));
+        mvs
+    }
+
+    fn drops(&self, board: BoardId) -> Vec<Move> {
+        let b = &self.boards[board];
+        move_gen::drops(b, &self.pockets[board][b.color_to_move()].kinds())
+    }
+
+    /// Makes a move or drop on the given board, checking to see if it is legal first. The color
+    /// of a dropped piece is ignored, since it always belongs to the side to move.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if the move is not legal, or if a dropped piece is not in hand.
+    pub fn make(&mut self, board: BoardId, mv: Move) -> Result<(), BoardError> {
+        let b = &self.boards[board];
+        let color = b.color_to_move();
+        let promoted = self.promoted[board];
+
+        if mv.is_drop() {
+            let kind = mv.promotion.kind().expect("Drops place a piece");
+            let mv = Move::drop(Piece::Filled(kind, color), mv.dest);
+            if self.pockets[board][color].count(kind) == 0 {
+                return Err(MoveError::NotInHand(mv.promotion).into());
+            }
+            if !move_gen::drops(b, &[kind]).contains(&mv) {
+                return Err(MoveError::IllegalDestination(mv).into());
+            }
+            let san = b.to_san(mv);
+            self.pockets[board][color].remove(kind);
+            unsafe {
+                self.boards[board].make_unchecked(mv);
+            }
+            self.end_turn(board, color);
+            self.history.push(BughouseMove {
+                board,
+                mv,
+                san,
+                transfer: None,
+                promoted,
+            });
+            return Ok(());
+        }
+
+        let captured = if b[mv.dest].is_color(!color) {
+            b[mv.dest].kind()
+        } else if b[mv.origin].is_kind(PieceKind::Pawn) && Some(mv.dest) == b.ep_target() {
+            Some(PieceKind::Pawn)
+        } else {
+            None
+        };
+        let transfer = captured.map(|kind| {
+            if promoted.contains(mv.dest) {
+                PieceKind::Pawn
+            } else {
+                kind
+            }
+        });
+        b.check_legal(mv)?;
+        let san = b.to_san(mv);
+        unsafe {
+            self.boards[board].make_unchecked(mv);
+        }
+
+        // The captured piece goes to the capturer's partner, who plays the other color
+        if let Some(kind) = transfer {
+            self.pockets[!board][!color].add(kind);
+        }
+        let dest: Bitboard = mv.dest.into();
+        let mut now_promoted = promoted & !dest;
+        if promoted.contains(mv.origin) || mv.promotion != Piece::Empty {
+            now_promoted = (now_promoted & !Bitboard::from(mv.origin)) | dest;
+        }
+        self.promoted[board] = now_promoted;
+
+        self.end_turn(board, color);
+        self.history.push(BughouseMove {
+            board,
+            mv,
+            san,
+            transfer,
+            promoted,
+        });
+        Ok(())
+    }
+
+    fn end_turn(&mut self, board: BoardId, color: Color) {
+        if let Some(clocks) = self.clocks.as_mut() {
+            clocks[board].end_turn(color);
+        }
+    }
+
+    /// Unmakes the last move made on either board. If no moves have been made, nothing happens.
+    /// Clocks are not wound back.
+    pub fn unmake(&mut self) {
+        let Some(last) = self.history.pop() else {
+            return;
+        };
+        self.boards[last.board].unmake();
+        let color = self.boards[last.board].color_to_move();
+        if let Some(kind) = last.transfer {
+            self.pockets[!last.board][!color].remove(kind);
+        }
+        if last.mv.is_drop() {
+            let kind = last.mv.promotion.kind().expect("Drops place a piece");
+            self.pockets[last.board][color].add(kind);
+        }
+        self.promoted[last.board] = last.promoted;
+    }
+
+    /// Subtracts the elapsed time from the clock of the side to move on the given board. Does
+    /// nothing in an untimed match.
+    pub fn tick(&mut self, board: BoardId, elapsed: Duration) {
+        let color = self.boards[board].color_to_move();
+        if let Some(clocks) = self.clocks.as_mut() {
+            clocks[board].tick(color, elapsed);
+        }
+    }
+
+    /// Returns the time left for the given [Color] on the given board, or `None` in an untimed
+    /// match.
+    pub fn time_left(&self, board: BoardId, color: Color) -> Option<Duration> {
+        self.clocks
+            .as_ref()
+            .map(|clocks| clocks[board].remaining(color))
+    }
+
+    /// Returns the [Clock] of the given board, or `None` in an untimed match
+    pub fn clock(&self, board: BoardId) -> Option<&Clock> {
+        self.clocks.as_ref().map(|clocks| &clocks[board])
+    }
+
+    /// Returns the outcome of the match if either board has finished. A player who has run out of
+    /// time loses, and a mate or stalemate only counts if no drop can get out of it.
+    pub fn outcome(&self) -> Option<BughouseOutcome> {
+        // Material can always arrive from the pocket, so running out of time always loses
+        for board in [BoardId::A, BoardId::B] {
+            if let Some(color) = self.clock(board).and_then(Clock::flagged) {
+                return Some(BughouseOutcome {
+                    board,
+                    outcome: Outcome::Win {
+                        winner: !color,
+                        reason: WinReason::Timeout,
+                    },
+                });
+            }
+        }
+        for board in [BoardId::A, BoardId::B] {
+            // Material can always come back from the pocket, so only the variant's own rules
+            // end a board
+            let b = &self.boards[board];
+            if let Some(outcome) = b.variant().outcome(b) {
+                if self.drops(board).is_empty() {
+                    return Some(BughouseOutcome { board, outcome });
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the value of a tag used when exporting to BPGN
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.get(name)
+    }
+
+    /// Sets a tag to be written when exporting to BPGN, like `WhiteA` or `Event`. The `Result`
+    /// tag is always written from the outcome of the match.
+    pub fn set_tag(&mut self, name: &str, value: &str) -> &mut Self {
+        self.tags.set(name, value);
+        self
+    }
+
+    /// Writes the match in Bughouse Portable Game Notation (BPGN). Moves are numbered per board,
+    /// with `A` and `B` marking white's moves and `a` and `b` black's.
+    pub fn to_bpgn(&self) -> String {
+        let result = match self.outcome().map(|o| o.winning_team()) {
+            Some(Some(Color::White)) => "1-0",
+            Some(Some(Color::Black)) => "0-1",
+            Some(None) => "1/2-1/2",
+            None => "*",
+        };
+
+        let mut output = String::new();
+        self.tags.write(&mut output, &["Result"]);
+        output += &format!("[Result \"{result}\"]\n\n");
+
+        let mut fullmoves = [1; 2];
+        let mut colors = [Color::White; 2];
+        let mut line = String::new();
+        for entry in &self.history {
+            let letter = match (entry.board, colors[entry.board]) {
+                (BoardId::A, Color::White) => 'A',
+                (BoardId::A, Color::Black) => 'a',
+                (BoardId::B, Color::White) => 'B',
+                (BoardId::B, Color::Black) => 'b',
+            };
+            let token = format!("{}{}. {}", fullmoves[entry.board], letter, entry.san);
+            if colors[entry.board] == Color::Black {
+                fullmoves[entry.board] += 1;
+            }
+            colors[entry.board] = !colors[entry.board];
+
+            if !line.is_empty() && line.len() + token.len() >= 80 {
+                output += &line;
+                output.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line += &token;
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        output + &line + result + "\n"
+    }
+
+    /// Reads a match from Bughouse Portable Game Notation (BPGN). Tags are kept, comments are
+    /// ignored, and every move is checked to be legal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if a move is improperly formatted, played on the wrong turn, or
+    /// illegal.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::{BoardId, Bughouse};
+    /// let bpgn = "[WhiteA \"Alice\"]\n\n1A. e4 1B. d4 1a. d5 {a comment} 2A. exd5 1b. P@e5 2B. dxe5 *";
+    /// let bughouse = Bughouse::from_bpgn(bpgn)?;
+    ///
+    /// assert_eq!(bughouse.tag("WhiteA"), Some("Alice"));
+    /// assert_eq!(bughouse[BoardId::B].to_fen(), "rnbqkbnr/pppppppp/8/4P3/8/8/PPP1PPPP/RNBQKBNR b KQkq - 0 2");
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn from_bpgn(bpgn: &str) -> Result<Self, BoardError> {
+        let mut bughouse = Self::new();
+        let comment_re = Regex::new(r"\{[^}]*\}").expect("Invalid Regex for comments");
+        let move_re = Regex::new(r"(\d+)([AaBb])\.\s*(\S+)").expect("Invalid Regex for BPGN moves");
+
+        let (tags, movetext) = Tags::read(bpgn);
+        bughouse.tags = tags;
+        let movetext = comment_re.replace_all(&movetext, " ");
+
+        for caps in move_re.captures_iter(&movetext) {
+            let (board, color) = match &caps[2] {
+                "A" => (BoardId::A, Color::White),
+                "a" => (BoardId::A, Color::Black),
+                "B" => (BoardId::B, Color::White),
+                _ => (BoardId::B, Color::Black),
+            };
+            if bughouse.boards[board].color_to_move() != color {
+                return Err(NotationError::OutOfTurn(caps[0].to_owned()).into());
+            }
+            let mv = bughouse.boards[board].parse_san(&caps[3])?;
+            bughouse.make(board, mv)?;
+        }
+        Ok(bughouse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_promoted_piece_returns_as_pawn() {
+        let mut bughouse = Bughouse::new();
+        bughouse.boards[BoardId::A] = Board::from_fen("7k/P7/8/8/8/8/7K/1r6 w - - 0 1").unwrap();
+        bughouse.make(BoardId::A, "a7a8q".parse().unwrap()).unwrap();
+        bughouse.make(BoardId::A, "b1b8".parse().unwrap()).unwrap();
+        bughouse.make(BoardId::A, "a8b8".parse().unwrap()).unwrap();
+        // A captured piece that was never promoted goes to the partner as itself
+        assert_eq!(
+            bughouse
+                .pocket(BoardId::B, Color::White)
+                .count(PieceKind::Pawn),
+            0
+        );
+        assert_eq!(
+            bughouse
+                .pocket(BoardId::B, Color::Black)
+                .count(PieceKind::Rook),
+            1
+        );
+        bughouse.unmake();
+        bughouse.make(BoardId::A, "h2g2".parse().unwrap()).unwrap();
+        bughouse.make(BoardId::A, "b8a8".parse().unwrap()).unwrap();
+        assert_eq!(
+            bughouse
+                .pocket(BoardId::B, Color::White)
+                .count(PieceKind::Pawn),
+            1
+        );
+        assert_eq!(
+            bughouse
+                .pocket(BoardId::B, Color::White)
+                .count(PieceKind::Queen),
+            0
+        );
+
+        while !bughouse.history.is_empty() {
+            bughouse.unmake();
+        }
+        assert!(bughouse.pocket(BoardId::B, Color::White).is_empty());
+        assert!(bughouse.pocket(BoardId::B, Color::Black).is_empty());
+    }
+
+    #[test]
+    fn test_drop_blocks_mate() {
+        let mut bughouse = Bughouse::new();
+        for mv in ["f2f3", "e7e5", "g2g4", "d8h4"] {
+            bughouse.make(BoardId::A, mv.parse().unwrap()).unwrap();
+        }
+        assert_eq!(
+            bughouse.outcome().map(|o| o.winning_team()),
+            Some(Some(Color::Black))
+        );
+
+        // With a knight in hand, white can block the fool's mate
+        bughouse.unmake();
+        bughouse.pockets[BoardId::A][Color::White].add(PieceKind::Knight);
+        bughouse.make(BoardId::A, "d8h4".parse().unwrap()).unwrap();
+        assert!(bughouse[BoardId::A].outcome().is_some());
+        assert_eq!(bughouse.outcome(), None);
+        assert_eq!(
+            bughouse.legal_moves(BoardId::A),
+            vec!["N@g3".parse().unwrap(), "N@f2".parse().unwrap()]
+        );
+        assert!(bughouse.make(BoardId::A, "N@a3".parse().unwrap()).is_err());
+        assert!(bughouse.make(BoardId::A, "B@g3".parse().unwrap()).is_err());
+        bughouse.make(BoardId::A, "N@g3".parse().unwrap()).unwrap();
+        assert!(bughouse.pocket(BoardId::A, Color::White).is_empty());
+        bughouse.unmake();
+        assert_eq!(
+            bughouse
+                .pocket(BoardId::A, Color::White)
+                .count(PieceKind::Knight),
+            1
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut bughouse = Bughouse::with_time(Duration::from_secs(60));
+        bughouse.tick(BoardId::B, Duration::from_secs(61));
+        let outcome = bughouse.outcome().unwrap();
+        assert_eq!(outcome.board, BoardId::B);
+        assert_eq!(outcome.winning_team(), Some(Color::White));
+    }
+
+    #[test]
+    fn test_bpgn_round_trip() {
+        let mut bughouse = Bughouse::new();
+        bughouse.set_tag("Event", "Club night");
+        let mvs = [
+            (BoardId::A, "e2e4"),
+            (BoardId::B, "d2d4"),
+            (BoardId::A, "d7d5"),
+            (BoardId::A, "e4d5"),
+            (BoardId::B, "P@e5"),
+            (BoardId::B, "d4e5"),
+            (BoardId::B, "b8c6"),
+        ];
+        for (board, mv) in mvs {
+            bughouse.make(board, mv.parse().unwrap()).unwrap();
+        }
+        let bpgn = bughouse.to_bpgn();
+        assert_eq!(
+            bpgn,
+            "[Event \"Club night\"]\n[Result \"*\"]\n\n1A. e4 1B. d4 1a. d5 2A. exd5 1b. P@e5 2B. dxe5 2b. Nc6 *\n"
+        );
+        let parsed = Bughouse::from_bpgn(&bpgn).unwrap();
+        assert_eq!(parsed[BoardId::A], bughouse[BoardId::A]);
+        assert_eq!(parsed[BoardId::B], bughouse[BoardId::B]);
+        assert_eq!(parsed.to_bpgn(), bpgn);
+    }
+}
@@ -10,3 +10,15 @@ pub enum Check {
     Single(Square),
     Double,
 }
+
+/// How a move gives check, as returned by [Board::gives_check_kind](crate::Board::gives_check_kind)
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CheckKind {
+    /// The moved piece attacks the king
+    Direct,
+    /// Moving the piece out of the way lets another piece attack the king
+    Discovered,
+    /// Both at once
+    Double,
+}
@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Board, Color, DrawReason, Outcome, WinReason};
+
+/// How time is added to a player's clock around each of their moves
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Increment {
+    #[default]
+    None,
+    /// The time is added after every move
+    Fischer(Duration),
+    /// The time used on a move is given back after it, up to this much
+    Bronstein(Duration),
+    /// The clock only starts running this long into every move (simple or US delay)
+    Delay(Duration),
+}
+
+/// One stage of a [TimeControl]: the time a player gets for a number of moves, or for the rest
+/// of the game when `moves` is `None`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Period {
+    pub moves: Option<u32>,
+    pub time: Duration,
+    pub increment: Increment,
+}
+
+/// The time each player has for the game, as one or more [Period]s. Once the moves of a period
+/// have been made, the time of the next one is added to the clock. A final period with a number
+/// of moves repeats for the rest of the game.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use chb_chess::{Increment, Period, TimeControl};
+/// // 90 minutes for 40 moves, then 30 minutes for the rest of the game, with 30 seconds added
+/// // after every move
+/// let increment = Increment::Fischer(Duration::from_secs(30));
+/// let classical = TimeControl::new(vec![
+///     Period { moves: Some(40), time: Duration::from_secs(90 * 60), increment },
+///     Period { moves: None, time: Duration::from_secs(30 * 60), increment },
+/// ]);
+/// assert_eq!(classical.periods().len(), 2);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeControl {
+    periods: Vec<Period>,
+}
+
+impl TimeControl {
+    /// Creates a time control from its periods.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `periods` is empty.
+    pub fn new(periods: Vec<Period>) -> Self {
+        assert!(
+            !periods.is_empty(),
+            "A time control needs at least one period"
+        );
+        Self { periods }
+    }
+
+    /// The same time for the whole game, with nothing added
+    pub fn sudden_death(time: Duration) -> Self {
+        Self::single(time, Increment::None)
+    }
+
+    /// The same time for the whole game, with `increment` added after every move
+    pub fn fischer(time: Duration, increment: Duration) -> Self {
+        Self::single(time, Increment::Fischer(increment))
+    }
+
+    /// The same time for the whole game, with up to `delay` of the time used on each move given
+    /// back after it
+    pub fn bronstein(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Increment::Bronstein(delay))
+    }
+
+    /// The same time for the whole game, with the clock waiting `delay` before it starts
+    /// running on each move
+    pub fn simple_delay(time: Duration, delay: Duration) -> Self {
+        Self::single(time, Increment::Delay(delay))
+    }
+
+    fn single(time: Duration, increment: Increment) -> Self {
+        Self::new(vec![Period {
+            moves: None,
+            time,
+            increment,
+        }])
+    }
+
+    pub fn periods(&self) -> &[Period] {
+        &self.periods
+    }
+}
+
+/// A chess clock keeping both players' remaining time under a [TimeControl].
+///
+/// The clock doesn't measure time itself. The time a player spends is passed to [Clock::tick],
+/// as often as is convenient, and [Clock::end_turn] is called once they have moved. A player
+/// whose time reaches zero has flagged, and the clock stops.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use chb_chess::{Clock, Color, TimeControl};
+/// let mut clock = Clock::new(TimeControl::fischer(Duration::from_secs(180), Duration::from_secs(2)));
+///
+/// clock.tick(Color::White, Duration::from_secs(5));
+/// clock.end_turn(Color::White);
+/// assert_eq!(clock.remaining(Color::White), Duration::from_secs(177));
+///
+/// clock.tick(Color::Black, Duration::from_secs(200));
+/// assert_eq!(clock.flagged(), Some(Color::Black));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Clock {
+    control: TimeControl,
+    remaining: [Duration; 2],
+    /// Time spent on the current move, which delays and Bronstein increments depend on
+    spent: [Duration; 2],
+    period: [usize; 2],
+    /// Moves made in the current period
+    period_moves: [u32; 2],
+    flagged: Option<Color>,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let time = control.periods[0].time;
+        Self {
+            control,
+            remaining: [time; 2],
+            spent: [Duration::ZERO; 2],
+            period: [0; 2],
+            period_moves: [0; 2],
+            flagged: None,
+        }
+    }
+
+    pub fn control(&self) -> &TimeControl {
+        &self.control
+    }
+
+    /// The time the given [Color] has left
+    pub fn remaining(&self, color: Color) -> Duration {
+        self.remaining[color]
+    }
+
+    /// The player who ran out of time, if either has
+    pub fn flagged(&self) -> Option<Color> {
+        self.flagged
+    }
+
+    /// The index of the [Period] the given [Color] is playing in
+    pub fn period(&self, color: Color) -> usize {
+        self.period[color]
+    }
+
+    /// The number of moves the given [Color] has left to make before the next period, or `None`
+    /// if the current period lasts the rest of the game
+    pub fn moves_to_go(&self, color: Color) -> Option<u32> {
+        self.current_period(color)
+            .moves
+            .map(|moves| moves - self.period_moves[color])
+    }
+
+    /// Charges `elapsed` thinking time to the given [Color], flagging them if their time runs
+    /// out. Under a simple delay, the first part of every move is free. Does nothing once
+    /// either player has flagged.
+    pub fn tick(&mut self, color: Color, elapsed: Duration) {
+        if self.flagged.is_some() {
+            return;
+        }
+        let before = self.spent[color];
+        self.spent[color] += elapsed;
+        let charge = match self.current_period(color).increment {
+            Increment::Delay(delay) => {
+                self.spent[color].saturating_sub(delay) - before.saturating_sub(delay)
+            }
+            _ => elapsed,
+        };
+
+        if charge >= self.remaining[color] {
+            self.remaining[color] = Duration::ZERO;
+            self.flagged = Some(color);
+        } else {
+            self.remaining[color] -= charge;
+        }
+    }
+
+    /// Ends the turn of the given [Color] after they have moved, adding their increment and,
+    /// if the move completes a period, the time of the next one.
+    pub fn end_turn(&mut self, color: Color) {
+        if self.flagged.is_some() {
+            return;
+        }
+        let period = *self.current_period(color);
+        self.remaining[color] += match period.increment {
+            Increment::Fischer(increment) => increment,
+            Increment::Bronstein(delay) => self.spent[color].min(delay),
+            Increment::None | Increment::Delay(_) => Duration::ZERO,
+        };
+        self.spent[color] = Duration::ZERO;
+
+        self.period_moves[color] += 1;
+        if period.moves == Some(self.period_moves[color]) {
+            let next = (self.period[color] + 1).min(self.control.periods.len() - 1);
+            self.period[color] = next;
+            self.period_moves[color] = 0;
+            self.remaining[color] += self.control.periods[next].time;
+        }
+    }
+
+    /// Returns the [Outcome] if a player has flagged. They lose on time, unless their opponent
+    /// has no way to checkmate them on `board`, which makes the game a draw.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::time::Duration;
+    /// # use chb_chess::{Board, Clock, Color, DrawReason, Outcome, TimeControl};
+    /// let mut clock = Clock::new(TimeControl::sudden_death(Duration::from_secs(60)));
+    /// clock.tick(Color::White, Duration::from_secs(61));
+    ///
+    /// // Black only has a knight left
+    /// let board = Board::from_fen("8/8/4k3/8/8/2n5/4K3/8 w - - 0 1")?;
+    /// assert_eq!(
+    ///     clock.outcome(&board),
+    ///     Some(Outcome::Draw(DrawReason::TimeoutVsInsufficientMaterial))
+    /// );
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn outcome(&self, board: &Board) -> Option<Outcome> {
+        let loser = self.flagged?;
+        Some(if board.has_mating_material(!loser) {
+            Outcome::Win {
+                winner: !loser,
+                reason: WinReason::Timeout,
+            }
+        } else {
+            Outcome::Draw(DrawReason::TimeoutVsInsufficientMaterial)
+        })
+    }
+
+    fn current_period(&self, color: Color) -> &Period {
+        &self.control.periods[self.period[color]]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Clock, Color, Increment, Period, TimeControl};
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_delays() {
+        let mut clock = Clock::new(TimeControl::simple_delay(secs(60), secs(5)));
+        clock.tick(Color::White, secs(3));
+        clock.tick(Color::White, secs(4));
+        assert_eq!(clock.remaining(Color::White), secs(58));
+        clock.end_turn(Color::White);
+        clock.tick(Color::White, secs(4));
+        assert_eq!(clock.remaining(Color::White), secs(58));
+
+        // Bronstein gives back the time used, but never more than the delay
+        let mut clock = Clock::new(TimeControl::bronstein(secs(60), secs(5)));
+        clock.tick(Color::White, secs(3));
+        clock.end_turn(Color::White);
+        assert_eq!(clock.remaining(Color::White), secs(60));
+        clock.tick(Color::White, secs(8));
+        clock.end_turn(Color::White);
+        assert_eq!(clock.remaining(Color::White), secs(57));
+    }
+
+    #[test]
+    fn test_periods() {
+        let control = TimeControl::new(vec![
+            Period {
+                moves: Some(2),
+                time: secs(60),
+                increment: Increment::None,
+            },
+            Period {
+                moves: Some(1),
+                time: secs(30),
+                increment: Increment::Fischer(secs(1)),
+            },
+        ]);
+        let mut clock = Clock::new(control);
+        assert_eq!(clock.moves_to_go(Color::Black), Some(2));
+        clock.tick(Color::Black, secs(10));
+        clock.end_turn(Color::Black);
+        clock.end_turn(Color::Black);
+        assert_eq!(clock.period(Color::Black), 1);
+        assert_eq!(clock.remaining(Color::Black), secs(80));
+
+        // The last period repeats, with its own increment
+        clock.end_turn(Color::Black);
+        assert_eq!(clock.period(Color::Black), 1);
+        assert_eq!(clock.remaining(Color::Black), secs(111));
+        assert_eq!(clock.remaining(Color::White), secs(60));
+    }
+}
@@ -0,0 +1,456 @@
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Board, BoardBuilder, BoardError, FenOptions, Move, NotationError};
+
+/// Opcodes whose operands are each a move from the position
+const MOVE_OPCODES: [&str; 4] = ["am", "bm", "pm", "sm"];
+
+/// An operand of an EPD [Operation]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Operand {
+    /// A move, written in SAN
+    Move(Move),
+    Integer(i64),
+    /// A string, written in double quotes
+    String(String),
+    /// Any other unquoted operand
+    Symbol(String),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operand::Move(mv) => write!(f, "{mv}"),
+            Operand::Integer(n) => write!(f, "{n}"),
+            Operand::String(s) => write!(f, "\"{s}\""),
+            Operand::Symbol(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// An EPD operation, like `bm Nf3;` or `id "BK.01";`
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Operation {
+    pub opcode: String,
+    pub operands: Vec<Operand>,
+}
+
+/// A position in Extended Position Description (EPD): the first four fields of a FEN followed
+/// by operations that describe it, like the best move, an identifier or perft counts.
+///
+/// Move operands of `bm`, `am`, `pm`, `sm` and `pv` are read as SAN or UCI and resolved against
+/// the position, with `pv` followed move by move. They are written back as SAN. The `hmvc` and
+/// `fmvn` operations set the board's halfmove clock and fullmove number, and are written for
+/// any counters that aren't at their starting values.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{Epd, Move};
+/// let epd: Epd = r#"1k1r4/pp1b1R2/3q2pp/4p3/2B5/4Q3/PPP2B2/2K5 b - - bm Qd1+; id "BK.01";"#.parse()?;
+///
+/// assert_eq!(epd.best_moves(), ["d6d1".parse::<Move>()?]);
+/// assert_eq!(epd.id(), Some("BK.01"));
+/// assert_eq!(
+///     epd.to_string(),
+///     r#"1k1r4/pp1b1R2/3q2pp/4p3/2B5/4Q3/PPP2B2/2K5 b - - bm Qd1+; id "BK.01";"#
+/// );
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Epd {
+    board: Board,
+    operations: Vec<Operation>,
+}
+
+impl FromStr for Epd {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Epd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_epd())
+    }
+}
+
+impl Epd {
+    /// Creates an EPD of the given position with no operations
+    pub fn new(board: Board) -> Self {
+        Self {
+            board,
+            operations: Vec::new(),
+        }
+    }
+
+    /// Reads a position in EPD. The halfmove and fullmove counters of a full FEN may come
+    /// between the position and the operations, as in many perft suites, and the final `;`
+    /// may be left off.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if the position is not valid, an operation has no valid opcode or
+    /// an unclosed string, or a move operand is not a legal move.
+    pub fn parse(epd: &str) -> Result<Self, BoardError> {
+        let epd = epd.trim();
+        let mut fields = epd.splitn(5, char::is_whitespace);
+        let position: Vec<&str> = fields.by_ref().take(4).collect();
+        let mut rest = fields.next().unwrap_or_default().trim_start();
+
+        let mut fen = position.join(" ");
+        for _ in 0..2 {
+            let (counter, after) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if counter.is_empty() || !counter.bytes().all(|b| b.is_ascii_digit()) {
+                break;
+            }
+            fen = format!("{fen} {counter}");
+            rest = after.trim_start();
+        }
+        let (mut builder, _) = BoardBuilder::from_fen_with_options(&fen, &FenOptions::default())?;
+
+        let raw = tokenize(rest)?;
+        for (opcode, operands) in &raw {
+            let counter = match operands[..] {
+                [(ref n, false)] => n.parse().ok(),
+                _ => None,
+            };
+            match (opcode.as_str(), counter) {
+                ("hmvc", Some(n)) => builder.halfmove(n),
+                ("fmvn", Some(n)) => builder.fullmove(n),
+                _ => continue,
+            };
+        }
+        let board = builder.build()?;
+
+        let mut operations = Vec::new();
+        for (opcode, operands) in raw {
+            let operands = if MOVE_OPCODES.contains(&opcode.as_str()) {
+                operands
+                    .iter()
+                    .map(|(token, _)| resolve_move(&board, token).map(Operand::Move))
+                    .collect::<Result<_, _>>()?
+            } else if opcode == "pv" {
+                let mut line = board.clone();
+                let mut mvs = Vec::new();
+                for (token, _) in &operands {
+                    let mv = resolve_move(&line, token)?;
+                    line.make(mv)?;
+                    mvs.push(Operand::Move(mv));
+                }
+                mvs
+            } else {
+                operands
+                    .into_iter()
+                    .map(|(token, quoted)| match token.parse() {
+                        _ if quoted => Operand::String(token),
+                        Ok(n) => Operand::Integer(n),
+                        Err(_) => Operand::Symbol(token),
+                    })
+                    .collect()
+            };
+            operations.push(Operation { opcode, operands });
+        }
+        Ok(Self { board, operations })
+    }
+
+    /// Writes the position and its operations in EPD, with moves in SAN. A halfmove clock other
+    /// than 0 or fullmove number other than 1 is written as an `hmvc` or `fmvn` operation if
+    /// there isn't one already.
+    pub fn to_epd(&self) -> String {
+        let fen = self.board.to_fen();
+        let mut output: Vec<String> = fen.split(' ').take(4).map(str::to_owned).collect();
+        for operation in &self.operations {
+            let mut tokens = vec![operation.opcode.clone()];
+            let mut line = self.board.clone();
+            for operand in &operation.operands {
+                tokens.push(match operand {
+                    Operand::Move(mv) if line.legal_moves().contains(mv) => {
+                        let san = line.to_san(*mv);
+                        if operation.opcode == "pv" {
+                            let _ = line.make(*mv);
+                        }
+                        san
+                    }
+                    _ => operand.to_string(),
+                });
+            }
+            output.push(tokens.join(" ") + ";");
+        }
+        // Counters read from a full FEN are kept as operations, since EPD has no field for them
+        let counters = [
+            ("hmvc", self.board.halfmove(), 0),
+            ("fmvn", self.board.fullmove(), 1),
+        ];
+        for (opcode, counter, default) in counters {
+            if counter != default && self.operands(opcode).is_none() {
+                output.push(format!("{opcode} {counter};"));
+            }
+        }
+        output.join(" ")
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// All operations, in the order they were read or set
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// The operands of the operation with the given opcode
+    pub fn operands(&self, opcode: &str) -> Option<&[Operand]> {
+        self.operations
+            .iter()
+            .find(|op| op.opcode == opcode)
+            .map(|op| op.operands.as_slice())
+    }
+
+    /// Sets the operands of an operation, replacing the operation if there already is one with
+    /// the same opcode and adding it to the end otherwise
+    pub fn set_operation(&mut self, opcode: &str, operands: Vec<Operand>) -> &mut Self {
+        match self.operations.iter_mut().find(|op| op.opcode == opcode) {
+            Some(op) => op.operands = operands,
+            None => self.operations.push(Operation {
+                opcode: opcode.to_owned(),
+                operands,
+            }),
+        }
+        self
+    }
+
+    /// Removes the operation with the given opcode, returning its operands
+    pub fn remove_operation(&mut self, opcode: &str) -> Option<Vec<Operand>> {
+        let index = self.operations.iter().position(|op| op.opcode == opcode)?;
+        Some(self.operations.remove(index).operands)
+    }
+
+    /// The best moves of the position, from `bm`
+    pub fn best_moves(&self) -> Vec<Move> {
+        self.moves("bm")
+    }
+
+    /// The moves to avoid in the position, from `am`
+    pub fn avoid_moves(&self) -> Vec<Move> {
+        self.moves("am")
+    }
+
+    /// The principal variation, from `pv`
+    pub fn pv(&self) -> Vec<Move> {
+        self.moves("pv")
+    }
+
+    /// The identifier of the position, from `id`
+    pub fn id(&self) -> Option<&str> {
+        self.string("id")
+    }
+
+    /// The comment with the given number from 0 to 9, from `c0` to `c9`
+    pub fn comment(&self, n: u8) -> Option<&str> {
+        self.string(&format!("c{n}"))
+    }
+
+    /// The depth the position was analyzed to, in plies, from `acd`
+    pub fn depth(&self) -> Option<u32> {
+        self.integer("acd")?.try_into().ok()
+    }
+
+    /// The evaluation of the position in centipawns for the side to move, from `ce`
+    pub fn eval(&self) -> Option<i32> {
+        self.integer("ce")?.try_into().ok()
+    }
+
+    /// The number of leaf nodes of a perft to the given depth, from `D1`, `D2` and so on
+    pub fn perft(&self, depth: usize) -> Option<u64> {
+        self.integer(&format!("D{depth}"))?.try_into().ok()
+    }
+
+    /// Every perft depth given with its number of leaf nodes, in the order they were given
+    pub fn perft_counts(&self) -> Vec<(usize, u64)> {
+        self.operations
+            .iter()
+            .filter_map(|op| {
+                let depth = op.opcode.strip_prefix('D')?.parse().ok()?;
+                Some((depth, self.perft(depth)?))
+            })
+            .collect()
+    }
+
+    fn moves(&self, opcode: &str) -> Vec<Move> {
+        self.operands(opcode)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|operand| match operand {
+                Operand::Move(mv) => Some(*mv),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn string(&self, opcode: &str) -> Option<&str> {
+        match self.operands(opcode)? {
+            [Operand::String(s) | Operand::Symbol(s)] => Some(s),
+            _ => None,
+        }
+    }
+
+    fn integer(&self, opcode: &str) -> Option<i64> {
+        match self.operands(opcode)? {
+            [Operand::Integer(n)] => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+/// An operand as it was written, and whether it was quoted
+type RawOperand = (String, bool);
+
+/// Splits operations into their opcodes and operands
+fn tokenize(text: &str) -> Result<Vec<(String, Vec<RawOperand>)>, BoardError> {
+    let mut operations = Vec::new();
+    let mut tokens: Vec<RawOperand> = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut finish = |tokens: &mut Vec<RawOperand>| -> Result<(), BoardError> {
+        if tokens.is_empty() {
+            return Ok(());
+        }
+        let (opcode, quoted) = tokens.remove(0);
+        let valid = !quoted
+            && opcode.len() <= 15
+            && opcode.starts_with(|c: char| c.is_ascii_alphabetic())
+            && opcode
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(NotationError::InvalidOpcode(opcode).into());
+        }
+        operations.push((opcode, std::mem::take(tokens)));
+        Ok(())
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => finish(&mut tokens)?,
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => s.push(c),
+                        None => return Err(NotationError::UnterminatedString.into()),
+                    }
+                }
+                tokens.push((s, true));
+            }
+            c if c.is_whitespace() => (),
+            c => {
+                let mut s = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == ';' || c == '"' {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push((s, false));
+            }
+        }
+    }
+    finish(&mut tokens)?;
+    Ok(operations)
+}
+
+/// Finds the legal move given in SAN, or in UCI if it isn't SAN
+fn resolve_move(board: &Board, token: &str) -> Result<Move, BoardError> {
+    let san_err = match board.parse_san(token) {
+        Ok(mv) => return Ok(mv),
+        Err(err) => err,
+    };
+    let Ok(uci) = token.parse::<Move>() else {
+        return Err(san_err);
+    };
+    board
+        .legal_moves()
+        .into_iter()
+        .find(|mv| {
+            mv.origin == uci.origin
+                && mv.dest == uci.dest
+                && mv.promotion.kind() == uci.promotion.kind()
+        })
+        .ok_or(san_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Epd, Move, NotationError, Operand};
+
+    #[test]
+    fn test_operations() {
+        let epd: Epd = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - \
+            bm Bb5 d2d4; am Qe2; pv Bb5 a7a6 Ba4; acd 12; ce -15; c0 \"Spanish; or Scotch\"; \
+            hmvc 2; fmvn 3; D1 27;"
+            .parse()
+            .unwrap();
+        let mv = |s: &str| s.parse::<Move>().unwrap();
+
+        assert_eq!(epd.best_moves(), [mv("f1b5"), mv("d2d4")]);
+        assert_eq!(epd.avoid_moves(), [mv("d1e2")]);
+        assert_eq!(epd.pv(), [mv("f1b5"), mv("a7a6"), mv("b5a4")]);
+        assert_eq!(epd.depth(), Some(12));
+        assert_eq!(epd.eval(), Some(-15));
+        assert_eq!(epd.comment(0), Some("Spanish; or Scotch"));
+        assert_eq!(epd.perft_counts(), [(1, 27)]);
+        assert_eq!(epd.board().halfmove(), 2);
+        assert_eq!(epd.board().fullmove(), 3);
+        assert_eq!(
+            epd.to_epd(),
+            "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - \
+            bm Bb5 d4; am Qe2; pv Bb5 a6 Ba4; acd 12; ce -15; c0 \"Spanish; or Scotch\"; \
+            hmvc 2; fmvn 3; D1 27;"
+        );
+        assert_eq!(epd.to_string().parse::<Epd>().unwrap(), epd);
+    }
+
+    #[test]
+    fn test_perft_suite_line() {
+        // Perft suites often give full FENs and lead each operation with `;`
+        let epd: Epd = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8 ;D1 44 ;D2 1486"
+            .parse()
+            .unwrap();
+        assert_eq!(epd.board().fullmove(), 8);
+        assert_eq!(epd.perft(2), Some(1486));
+        assert_eq!(
+            epd.to_epd(),
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - D1 44; D2 1486; hmvc 1; fmvn 8;"
+        );
+        // The counters come back as operations
+        let read: Epd = epd.to_string().parse().unwrap();
+        assert_eq!(read.board(), epd.board());
+        assert_eq!(read.to_epd(), epd.to_epd());
+
+        let mut epd = Epd::new(epd.board().clone());
+        epd.set_operation("id", vec![Operand::String("Talkchess".to_owned())]);
+        assert_eq!(
+            epd.to_epd(),
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - id \"Talkchess\"; hmvc 1; \
+            fmvn 8;"
+        );
+
+        assert_eq!(
+            "8/8/8/8/8/8/8/k6K w - - bm Kb1".parse::<Epd>(),
+            Err(NotationError::NoMatchingMove("Kb1".to_owned()).into())
+        );
+        assert_eq!(
+            "8/8/8/8/8/8/8/k6K w - - 1x 2;".parse::<Epd>(),
+            Err(NotationError::InvalidOpcode("1x".to_owned()).into())
+        );
+    }
+}
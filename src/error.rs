@@ -1,58 +1,455 @@
-use std::{
-    char::ParseCharError,
-    fmt,
-    num::{self, ParseIntError},
-};
+use std::{error::Error, fmt};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ErrorKind {
-    InvalidInput,
-    OutOfBounds,
-}
+use crate::{Color, Move, Piece, Ray, Square};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct BoardError {
-    pub kind: ErrorKind,
-    pub message: &'static str,
+/// Every error this crate returns. Each variant wraps a more specific error describing what went
+/// wrong, which can be matched on to show a message of your own.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{Board, BoardError, FenError, FenField};
+/// let err = Board::from_fen("8/8/8/8/8/8/8/8 w KQx - 0 1").unwrap_err();
+/// match err {
+///     BoardError::Fen(FenError { field, text, .. }) => {
+///         assert_eq!(field, FenField::Castling);
+///         assert_eq!(text, "KQx");
+///     }
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BoardError {
+    /// A square, piece, color, move or variant couldn't be read from text
+    Parse(ParseError),
+    /// A FEN string couldn't be read
+    Fen(FenError),
+    /// A move couldn't be made in the current position
+    Move(MoveError),
+    /// A position breaks the rules of its variant
+    Position(PositionError),
+    /// SAN, PGN, BPGN or EPD couldn't be read
+    Notation(NotationError),
 }
 
 impl fmt::Display for BoardError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut err = match self.kind {
-            ErrorKind::InvalidInput => "Invalid Input",
-            ErrorKind::OutOfBounds => "Out of Bounds",
+        match self {
+            BoardError::Parse(err) => err.fmt(f),
+            BoardError::Fen(err) => err.fmt(f),
+            BoardError::Move(err) => err.fmt(f),
+            BoardError::Position(err) => err.fmt(f),
+            BoardError::Notation(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for BoardError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BoardError::Fen(err) => err.source(),
+            _ => None,
+        }
+    }
+}
+
+/// Text that doesn't describe what it was read as
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    Square(String),
+    /// An index past the last square, a1
+    SquareIndex(u64),
+    Piece(String),
+    /// An index past the last piece
+    PieceIndex(usize),
+    Color(String),
+    /// A move that isn't in UCI notation
+    Move(String),
+    Variant(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Square(s) => write!(f, "`{s}` is not a square"),
+            ParseError::SquareIndex(i) => write!(f, "{i} is not the index of a square"),
+            ParseError::Piece(s) => write!(f, "`{s}` is not a piece"),
+            ParseError::PieceIndex(i) => write!(f, "{i} is not the index of a piece"),
+            ParseError::Color(s) => write!(f, "`{s}` is not a color"),
+            ParseError::Move(s) => write!(f, "`{s}` is not a move in UCI notation"),
+            ParseError::Variant(s) => write!(f, "`{s}` is not a known variant"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl From<ParseError> for BoardError {
+    fn from(err: ParseError) -> Self {
+        BoardError::Parse(err)
+    }
+}
+
+/// The fields of a FEN string, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FenField {
+    Placement,
+    ColorToMove,
+    Castling,
+    EnPassant,
+    Halfmove,
+    Fullmove,
+}
+
+impl FenField {
+    /// The position of the field in a FEN string, starting at 0
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl fmt::Display for FenField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FenField::Placement => "piece placement",
+            FenField::ColorToMove => "color to move",
+            FenField::Castling => "castling",
+            FenField::EnPassant => "en passant",
+            FenField::Halfmove => "halfmove clock",
+            FenField::Fullmove => "fullmove number",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A field of a FEN string that couldn't be read. `text` is the field as it was given, and is
+/// empty if the field is missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FenError {
+    pub field: FenField,
+    pub text: String,
+    /// The error from reading a part of the field, like a piece or a square
+    pub source: Option<Box<BoardError>>,
+}
+
+impl FenError {
+    pub(crate) fn new(field: FenField, text: &str) -> Self {
+        Self {
+            field,
+            text: text.to_owned(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn with_source(mut self, source: impl Into<BoardError>) -> Self {
+        self.source = Some(Box::new(source.into()));
+        self
+    }
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.text.is_empty() {
+            write!(f, "FEN is missing its {} field", self.field)
+        } else {
+            write!(
+                f,
+                "Invalid {} field `{}` in FEN (field {})",
+                self.field,
+                self.text,
+                self.field.index() + 1
+            )
         }
-        .to_string();
+    }
+}
 
-        err += &format!(": {}", self.message);
+impl Error for FenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|err| err as &(dyn Error + 'static))
+    }
+}
 
-        write!(f, "{}", err)
+impl From<FenError> for BoardError {
+    fn from(err: FenError) -> Self {
+        BoardError::Fen(err)
     }
 }
 
-impl From<num::TryFromIntError> for BoardError {
-    fn from(_: num::TryFromIntError) -> Self {
-        BoardError::new(ErrorKind::OutOfBounds, "Invalid integer conversion")
+/// Why a move can't be made
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MoveError {
+    /// There is no piece on the origin square
+    NoPiece(Square),
+    /// The piece on the origin square belongs to the side not to move
+    WrongColor(Square),
+    /// The piece can't move to the destination, whatever the rest of the position
+    IllegalDestination(Move),
+    /// The piece could move to the destination, but a piece of its own is there
+    CaptureOwnPiece(Move),
+    /// The pawn reaches its last rank, but no piece to promote to was given
+    PromotionRequired(Move),
+    /// The piece could move to the destination, but the piece on the square stands in its way
+    Blocked(Move, Square),
+    /// The piece is pinned to its king along the [Ray] from the king, and the move leaves the
+    /// pin
+    Pinned(Move, Ray),
+    /// The king would move to a square the opponent attacks
+    KingIntoAttack(Move),
+    /// The king is in check from the piece on the square, and the move neither captures it,
+    /// blocks it nor moves the king
+    InCheck(Move, Square),
+    /// The king is in check from two pieces, so only the king can move
+    DoubleCheck(Move),
+    /// The move leaves or puts the mover's own king in check
+    LeavesKingInCheck(Move),
+    /// The king can't castle to that side since it or the rook has moved
+    CastlingRights(Move),
+    /// The king can't castle while in check
+    CastlingOutOfCheck(Move),
+    /// The king can't castle through or into the square, which the opponent attacks
+    CastlingThroughCheck(Move, Square),
+    /// A capture is available, so the move isn't allowed (Antichess)
+    CaptureRequired(Move),
+    /// The move gives check, which isn't allowed (Racing Kings)
+    GivesCheck(Move),
+    /// The side to move can't pass while in check
+    PassInCheck,
+    /// The dropped piece isn't in the player's hand (Bughouse)
+    NotInHand(Piece),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::NoPiece(square) => write!(f, "There is no piece on {square}"),
+            MoveError::WrongColor(square) => {
+                write!(f, "The piece on {square} belongs to the side not to move")
+            }
+            MoveError::IllegalDestination(mv) => {
+                write!(f, "The piece on {} can't move to {}", mv.origin, mv.dest)
+            }
+            MoveError::CaptureOwnPiece(mv) => {
+                write!(f, "The piece on {} can't capture its own side", mv.origin)
+            }
+            MoveError::PromotionRequired(mv) => {
+                write!(f, "The pawn on {} has to promote on {}", mv.origin, mv.dest)
+            }
+            MoveError::Blocked(mv, square) => {
+                write!(f, "The piece on {} is blocked on {square}", mv.origin)
+            }
+            MoveError::Pinned(mv, ray) => write!(
+                f,
+                "The piece on {} is pinned to its king on {}",
+                mv.origin, ray.origin
+            ),
+            MoveError::KingIntoAttack(mv) => {
+                write!(f, "The king can't move into check on {}", mv.dest)
+            }
+            MoveError::InCheck(mv, checker) => write!(
+                f,
+                "{mv} doesn't deal with the check from the piece on {checker}"
+            ),
+            MoveError::DoubleCheck(mv) => {
+                write!(f, "{mv} isn't a king move, but the king is in double check")
+            }
+            MoveError::LeavesKingInCheck(mv) => write!(f, "{mv} leaves the king in check"),
+            MoveError::CastlingRights(_) => {
+                write!(
+                    f,
+                    "Castling to that side isn't allowed since the king or rook has moved"
+                )
+            }
+            MoveError::CastlingOutOfCheck(_) => write!(f, "The king can't castle out of check"),
+            MoveError::CastlingThroughCheck(_, square) => {
+                write!(f, "The king can't castle through check on {square}")
+            }
+            MoveError::CaptureRequired(mv) => {
+                write!(f, "{mv} is not a capture, but a capture is available")
+            }
+            MoveError::GivesCheck(mv) => write!(f, "{mv} gives check"),
+            MoveError::PassInCheck => write!(f, "Cannot pass the turn while in check"),
+            MoveError::NotInHand(piece) => write!(f, "There is no `{piece}` in hand to drop"),
+        }
     }
 }
 
-impl From<ParseIntError> for BoardError {
-    fn from(_: ParseIntError) -> Self {
-        BoardError::new(ErrorKind::InvalidInput, "Unable to parse integer")
+impl Error for MoveError {}
+
+impl From<MoveError> for BoardError {
+    fn from(err: MoveError) -> Self {
+        BoardError::Move(err)
     }
 }
 
-impl From<ParseCharError> for BoardError {
-    fn from(_: ParseCharError) -> Self {
-        BoardError::new(ErrorKind::InvalidInput, "Unable to parse char")
+/// A rule the position of a board breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PositionError {
+    /// The side has a different number of kings than its variant requires
+    KingCount(Color),
+    /// Castling rights are given in a variant without castling
+    CastlingNotAllowed,
+    /// The side has castling rights but its king is not on its starting square
+    CastlingKingMoved(Color),
+    /// The en passant target is not behind a pawn that just made a double push
+    EnPassantTarget(Square),
+    /// The side not to move is in check
+    OpponentInCheck,
+    /// The side to move has no legal moves
+    NoLegalMoves,
+    /// A king is in check in a variant where that can't happen (Racing Kings)
+    CheckNotAllowed,
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionError::KingCount(Color::White) => {
+                write!(
+                    f,
+                    "Board has the wrong number of white kings for the variant"
+                )
+            }
+            PositionError::KingCount(Color::Black) => {
+                write!(
+                    f,
+                    "Board has the wrong number of black kings for the variant"
+                )
+            }
+            PositionError::CastlingNotAllowed => {
+                write!(f, "Castling is not allowed in this variant")
+            }
+            PositionError::CastlingKingMoved(Color::White) => {
+                write!(f, "White king may not castle if it is not at e1")
+            }
+            PositionError::CastlingKingMoved(Color::Black) => {
+                write!(f, "Black king may not castle if it is not at e8")
+            }
+            PositionError::EnPassantTarget(square) => {
+                write!(f, "{square} is not a possible en passant target")
+            }
+            PositionError::OpponentInCheck => {
+                write!(f, "The side not to move is in check")
+            }
+            PositionError::NoLegalMoves => write!(f, "The side to move has no legal moves"),
+            PositionError::CheckNotAllowed => {
+                write!(f, "Kings may not be in check in this variant")
+            }
+        }
     }
 }
 
-impl BoardError {
-    pub fn new(kind: ErrorKind, message: &'static str) -> BoardError {
-        BoardError {
-            kind,
-            message,
+impl Error for PositionError {}
+
+impl From<PositionError> for BoardError {
+    fn from(err: PositionError) -> Self {
+        BoardError::Position(err)
+    }
+}
+
+/// A problem reading a move in SAN, a game in PGN or BPGN, or a position in EPD
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NotationError {
+    /// The text is not SAN
+    InvalidSan(String),
+    /// The SAN matches no legal move
+    NoMatchingMove(String),
+    /// The SAN matches more than one legal move
+    AmbiguousSan(String),
+    /// A `{` comment is never closed
+    UnterminatedComment,
+    /// A `)` has no `(` before it
+    UnmatchedParenthesis,
+    /// A `(` variation is never closed
+    UnclosedVariation,
+    /// A variation starts before any move it could replace
+    VariationBeforeFirstMove,
+    InvalidNag(String),
+    /// A BPGN move is numbered for the side not to move on its board
+    OutOfTurn(String),
+    /// A `"` string is never closed
+    UnterminatedString,
+    /// An EPD operation doesn't start with a valid opcode
+    InvalidOpcode(String),
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotationError::InvalidSan(san) => write!(f, "`{san}` is not valid SAN"),
+            NotationError::NoMatchingMove(san) => write!(f, "`{san}` does not match a legal move"),
+            NotationError::AmbiguousSan(san) => write!(f, "`{san}` is ambiguous"),
+            NotationError::UnterminatedComment => write!(f, "Unterminated comment"),
+            NotationError::UnmatchedParenthesis => write!(f, "Unmatched parenthesis"),
+            NotationError::UnclosedVariation => write!(f, "Unclosed variation"),
+            NotationError::VariationBeforeFirstMove => {
+                write!(f, "Variation before the first move")
+            }
+            NotationError::InvalidNag(nag) => write!(f, "`{nag}` is not a valid NAG"),
+            NotationError::OutOfTurn(mv) => write!(f, "`{mv}` was played out of turn"),
+            NotationError::UnterminatedString => write!(f, "Unterminated string"),
+            NotationError::InvalidOpcode(opcode) => write!(f, "`{opcode}` is not a valid opcode"),
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+impl From<NotationError> for BoardError {
+    fn from(err: NotationError) -> Self {
+        BoardError::Notation(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{Board, BoardError, FenField, MoveError, ParseError};
+
+    #[test]
+    fn test_source_chain() {
+        let err = Board::from_fen("8/8/8/3x4/8/8/8/8 w - - 0 1").unwrap_err();
+        let BoardError::Fen(fen) = &err else {
+            panic!("Expected a FEN error, got {err:?}");
+        };
+        assert_eq!(fen.field, FenField::Placement);
+        assert_eq!(
+            err.source().and_then(|e| e.downcast_ref::<BoardError>()),
+            Some(&BoardError::Parse(ParseError::Piece("x".to_owned())))
+        );
+        assert_eq!(
+            err.to_string(),
+            "Invalid piece placement field `8/8/8/3x4/8/8/8/8` in FEN (field 1)"
+        );
+    }
+
+    #[test]
+    fn test_move_errors() {
+        let mut board = Board::from_fen("4k3/8/8/8/1b6/8/3P4/r3K2R w K - 0 1").unwrap();
+        let cases = [
+            ("e3e4", MoveError::NoPiece("e3".parse().unwrap())),
+            ("b4c3", MoveError::WrongColor("b4".parse().unwrap())),
+            (
+                "h1g3",
+                MoveError::IllegalDestination("h1g3".parse().unwrap()),
+            ),
+            (
+                "d2d3",
+                MoveError::Pinned(
+                    "d2d3".parse().unwrap(),
+                    board.pin_on_square("d2".parse().unwrap()).unwrap(),
+                ),
+            ),
+            ("e1d1", MoveError::KingIntoAttack("e1d1".parse().unwrap())),
+        ];
+        for (mv, err) in cases {
+            assert_eq!(board.make(mv.parse().unwrap()), Err(BoardError::Move(err)));
         }
     }
 }
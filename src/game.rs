@@ -0,0 +1,385 @@
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Board, BoardError, Color, Move};
+
+mod pgn;
+pub(crate) use pgn::Tags;
+
+/// Identifies a position in the move tree of a [Game]. The root is the starting position, and
+/// every other node is the position after a move.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Node {
+    mv: Option<Move>,
+    san: String,
+    parent: Option<NodeId>,
+    /// The first child continues the line, and the others are variations of it
+    children: Vec<NodeId>,
+    /// Number of moves from the starting position
+    ply: u32,
+    comment: Option<String>,
+    nags: Vec<u8>,
+    clock: Option<Duration>,
+}
+
+impl Node {
+    fn new(mv: Option<Move>, san: String, parent: Option<NodeId>, ply: u32) -> Self {
+        Self {
+            mv,
+            san,
+            parent,
+            children: Vec::new(),
+            ply,
+            comment: None,
+            nags: Vec::new(),
+            clock: None,
+        }
+    }
+}
+
+/// A game of chess as a tree of moves, with the main line, nested variations, comments, Numeric
+/// Annotation Glyphs (NAGs), clock times and PGN tags.
+///
+/// The game keeps a [Board] in the position of the current node, which is moved through the
+/// tree by making and unmaking moves.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::Game;
+/// let mut game = Game::new();
+/// game.make("e2e4".parse()?)?;
+/// game.make("e7e5".parse()?)?;
+///
+/// // Go back and add 1... c5 as a variation
+/// game.back();
+/// game.make("c7c5".parse()?)?;
+/// game.set_comment("The Sicilian");
+///
+/// assert_eq!(game.to_pgn().lines().last(), Some("1. e4 e5 (1... c5 {The Sicilian}) *"));
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Game {
+    tags: Tags,
+    start: Board,
+    nodes: Vec<Node>,
+    current: NodeId,
+    board: Board,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::from_board(Board::default())
+    }
+}
+
+impl Game {
+    const ROOT: NodeId = NodeId(0);
+
+    /// Creates a game from the standard starting position
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a game starting from the given position
+    pub fn from_board(board: Board) -> Self {
+        Self {
+            tags: Tags::default(),
+            start: board.clone(),
+            nodes: vec![Node::new(None, String::new(), None, 0)],
+            current: Self::ROOT,
+            board,
+        }
+    }
+
+    /// The position the game started from
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    /// The position at the current node
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn root(&self) -> NodeId {
+        Self::ROOT
+    }
+
+    pub fn current(&self) -> NodeId {
+        self.current
+    }
+
+    /// Returns the move that led to the given node, or `None` for the root
+    pub fn move_at(&self, node: NodeId) -> Option<Move> {
+        self.nodes[node.0].mv
+    }
+
+    /// Returns the parent of the given node, or `None` for the root
+    pub fn parent(&self, node: NodeId) -> Option<NodeId> {
+        self.nodes[node.0].parent
+    }
+
+    /// Returns the nodes that follow the given node. The first one continues the line and the
+    /// rest are its variations.
+    pub fn children(&self, node: NodeId) -> &[NodeId] {
+        &self.nodes[node.0].children
+    }
+
+    /// Returns the moves of the main line, from the start of the game to its end
+    pub fn mainline(&self) -> Vec<Move> {
+        let mut mvs = Vec::new();
+        let mut node = Self::ROOT;
+        while let Some(&next) = self.nodes[node.0].children.first() {
+            mvs.extend(self.nodes[next.0].mv);
+            node = next;
+        }
+        mvs
+    }
+
+    /// Makes a move from the current node and moves to the resulting node. If the move has
+    /// already been played from here, the existing node is used, and otherwise the move starts a
+    /// new variation, or continues the line if there was nothing after the current node.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if the move is not legal.
+    pub fn make(&mut self, mv: Move) -> Result<NodeId, BoardError> {
+        if let Some(&existing) = self.nodes[self.current.0]
+            .children
+            .iter()
+            .find(|child| self.nodes[child.0].mv == Some(mv))
+        {
+            self.board_make(mv)?;
+            self.current = existing;
+            return Ok(existing);
+        }
+
+        if !mv.is_null() {
+            self.board.check_legal(mv)?;
+        }
+        let san = self.board.to_san(mv);
+        self.board_make(mv)?;
+        let id = NodeId(self.nodes.len());
+        let ply = self.nodes[self.current.0].ply + 1;
+        self.nodes
+            .push(Node::new(Some(mv), san, Some(self.current), ply));
+        self.nodes[self.current.0].children.push(id);
+        self.current = id;
+        Ok(id)
+    }
+
+    fn board_make(&mut self, mv: Move) -> Result<(), BoardError> {
+        if mv.is_null() {
+            self.board.make_null()
+        } else {
+            self.board.make(mv)
+        }
+    }
+
+    /// Follows the line one move forward. Returns false if the current node is the end of its
+    /// line.
+    pub fn forward(&mut self) -> bool {
+        self.enter_variation(0)
+    }
+
+    /// Goes back one move. Returns false at the start of the game.
+    pub fn back(&mut self) -> bool {
+        match self.nodes[self.current.0].parent {
+            Some(parent) => {
+                self.board.unmake();
+                self.current = parent;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves forward into the child with the given index, where 0 continues the line and higher
+    /// indices are its variations. Returns false if there is no such child.
+    pub fn enter_variation(&mut self, index: usize) -> bool {
+        let Some(&child) = self.nodes[self.current.0].children.get(index) else {
+            return false;
+        };
+        let mv = self.nodes[child.0].mv.expect("Only the root has no move");
+        unsafe {
+            if mv.is_null() {
+                // Null moves in a game are only made where passing was allowed
+                let _ = self.board.make_null();
+            } else {
+                self.board.make_unchecked(mv);
+            }
+        }
+        self.current = child;
+        true
+    }
+
+    /// Moves to any node in the tree, keeping the board in sync
+    pub fn go_to(&mut self, node: NodeId) {
+        let mut path = Vec::new();
+        let mut n = node;
+        while let Some(parent) = self.nodes[n.0].parent {
+            path.push(n);
+            n = parent;
+        }
+        while self.back() {}
+        for n in path.into_iter().rev() {
+            let parent = &self.nodes[self.current.0];
+            let index = parent
+                .children
+                .iter()
+                .position(|child| *child == n)
+                .expect("Every node is a child of its parent");
+            self.enter_variation(index);
+        }
+    }
+
+    /// Makes the variation the current node is in the main continuation at the point where it
+    /// branches off. Returns false if the current node is already on the main line.
+    pub fn promote_variation(&mut self) -> bool {
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node.0].parent {
+            let children = &mut self.nodes[parent.0].children;
+            let index = children
+                .iter()
+                .position(|child| *child == node)
+                .expect("Every node is a child of its parent");
+            if index != 0 {
+                children.remove(index);
+                children.insert(0, node);
+                return true;
+            }
+            node = parent;
+        }
+        false
+    }
+
+    /// The comment after the current move, or before the first move at the root
+    pub fn comment(&self) -> Option<&str> {
+        self.nodes[self.current.0].comment.as_deref()
+    }
+
+    pub fn set_comment(&mut self, comment: &str) -> &mut Self {
+        self.nodes[self.current.0].comment = Some(comment.to_owned());
+        self
+    }
+
+    /// The Numeric Annotation Glyphs of the current move, like 1 for `!` or 4 for `??`
+    pub fn nags(&self) -> &[u8] {
+        &self.nodes[self.current.0].nags
+    }
+
+    pub fn add_nag(&mut self, nag: u8) -> &mut Self {
+        let nags = &mut self.nodes[self.current.0].nags;
+        if !nags.contains(&nag) {
+            nags.push(nag);
+        }
+        self
+    }
+
+    /// The time left on the mover's clock after the current move, written as `[%clk]` in PGN
+    pub fn clock(&self) -> Option<Duration> {
+        self.nodes[self.current.0].clock
+    }
+
+    pub fn set_clock(&mut self, clock: Duration) -> &mut Self {
+        self.nodes[self.current.0].clock = Some(clock);
+        self
+    }
+
+    /// Returns the value of a PGN tag, like `White` or `Event`
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.get(name)
+    }
+
+    /// Sets a PGN tag, keeping the order tags were first set in. The `Variant`, `SetUp` and
+    /// `FEN` tags are written from the starting position when exporting to PGN.
+    pub fn set_tag(&mut self, name: &str, value: &str) -> &mut Self {
+        self.tags.set(name, value);
+        self
+    }
+
+    /// Returns all PGN tags in order
+    pub fn tags(&self) -> &[(String, String)] {
+        self.tags.all()
+    }
+
+    /// The move number and color that made the move at the given node
+    fn move_number(&self, node: NodeId) -> (u32, Color) {
+        let start =
+            (self.start.fullmove() - 1) * 2 + u32::from(self.start.color_to_move() == Color::Black);
+        let ply = start + self.nodes[node.0].ply - 1;
+        let color = if (ply & 1) == 0 {
+            Color::White
+        } else {
+            Color::Black
+        };
+        (ply / 2 + 1, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Board, Game};
+
+    #[test]
+    fn test_navigation() {
+        let mut game = Game::new();
+        for mv in ["e2e4", "e7e5", "g1f3"] {
+            game.make(mv.parse().unwrap()).unwrap();
+        }
+        let nf3 = game.current();
+        game.back();
+        game.back();
+        let c5 = game.make("c7c5".parse().unwrap()).unwrap();
+        game.make("g1f3".parse().unwrap()).unwrap();
+
+        // Making a move that already exists follows it
+        game.go_to(game.root());
+        assert_eq!(game.board(), &Board::default());
+        game.make("e2e4".parse().unwrap()).unwrap();
+        assert_eq!(game.children(game.current()).len(), 2);
+        assert!(game.enter_variation(1));
+        assert_eq!(game.current(), c5);
+        assert!(game.forward());
+        assert!(!game.forward());
+
+        assert!(game.promote_variation());
+        assert_eq!(game.mainline().len(), 3);
+        assert_eq!(game.mainline()[1], "c7c5".parse().unwrap());
+        assert!(!game.promote_variation());
+
+        game.go_to(nf3);
+        assert_eq!(
+            game.board().to_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+        assert!(game.make("e2e4".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_annotations() {
+        let mut game = Game::new();
+        game.set_tag("White", "Alice").set_tag("Black", "Bob");
+        game.make("d2d4".parse().unwrap()).unwrap();
+        game.add_nag(1)
+            .set_clock(Duration::from_secs(299))
+            .set_comment("Solid");
+        assert_eq!(game.nags(), &[1]);
+        assert_eq!(game.clock(), Some(Duration::from_secs(299)));
+        assert_eq!(game.comment(), Some("Solid"));
+        assert_eq!(game.tag("White"), Some("Alice"));
+        game.back();
+        assert_eq!(game.comment(), None);
+    }
+}
@@ -0,0 +1,427 @@
+use std::time::Duration;
+
+use regex::Regex;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardBuilder, BoardError, Color, Game, NodeId, NotationError, Variant};
+
+/// Move suffixes and the NAGs they stand for
+const SUFFIX_NAGS: [(&str, u8); 6] = [
+    ("!!", 3),
+    ("??", 4),
+    ("!?", 5),
+    ("?!", 6),
+    ("!", 1),
+    ("?", 2),
+];
+
+/// The tags of a PGN or BPGN game, in the order they were first set
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub(crate) struct Tags(Vec<(String, String)>);
+
+impl Tags {
+    pub(crate) fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a tag, keeping its place if it was already set
+    pub(crate) fn set(&mut self, name: &str, value: &str) {
+        match self.0.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value.to_owned(),
+            None => self.0.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    pub(crate) fn all(&self) -> &[(String, String)] {
+        &self.0
+    }
+
+    /// Writes every tag but the ones the game writes itself
+    pub(crate) fn write(&self, output: &mut String, written: &[&str]) {
+        for (name, value) in self
+            .0
+            .iter()
+            .filter(|(n, _)| !written.contains(&n.as_str()))
+        {
+            *output += &format!("[{name} \"{value}\"]\n");
+        }
+    }
+
+    /// Splits the text of a game into its tags and its movetext. Tags are only read before the
+    /// movetext starts, and lines starting with `%` are skipped.
+    pub(crate) fn read(text: &str) -> (Self, String) {
+        let tag_re = Regex::new(r#"^\s*\[(\w+)\s+"(.*)"\]\s*$"#).expect("Invalid Regex for tags");
+        let mut tags = Self::default();
+        let mut movetext = String::new();
+        for line in text.lines() {
+            if line.starts_with('%') {
+                continue;
+            }
+            match tag_re.captures(line) {
+                Some(caps) if movetext.trim().is_empty() => tags.set(&caps[1], &caps[2]),
+                _ => {
+                    movetext += line;
+                    movetext.push('\n');
+                }
+            }
+        }
+        (tags, movetext)
+    }
+}
+
+impl Game {
+    /// Writes the game in Portable Game Notation (PGN), with variations, comments, NAGs and
+    /// `[%clk]` clock times. Games of a variant other than Standard get a `Variant` tag, and
+    /// games that don't start from the starting position of their variant get `SetUp` and `FEN`
+    /// tags.
+    pub fn to_pgn(&self) -> String {
+        let result = self.tag("Result").unwrap_or("*");
+
+        let mut output = String::new();
+        self.tags
+            .write(&mut output, &["Result", "Variant", "FEN", "SetUp"]);
+        let variant = self.start.variant();
+        if variant != Variant::Standard {
+            output += &format!("[Variant \"{variant}\"]\n");
+        }
+        // The variant has its own tag, so the FEN keeps to the six standard fields
+        let fen = self.start.to_fen();
+        let fen = fen.split(' ').take(6).collect::<Vec<_>>().join(" ");
+        if fen != variant.starting_fen() {
+            output += &format!("[SetUp \"1\"]\n[FEN \"{fen}\"]\n");
+        }
+        output += &format!("[Result \"{result}\"]\n\n");
+
+        let mut tokens = Vec::new();
+        if let Some(comment) = self.comment_text(Self::ROOT) {
+            tokens.push(comment);
+        }
+        self.write_children(Self::ROOT, &mut tokens, true);
+        tokens.push(result.to_owned());
+
+        let mut line = String::new();
+        for token in tokens {
+            let glued = line.ends_with('(') || token == ")";
+            if !line.is_empty() && !glued && line.len() + token.len() >= 80 {
+                output += &line;
+                output.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() && !glued {
+                line.push(' ');
+            }
+            line += &token;
+        }
+        output + &line + "\n"
+    }
+
+    /// Writes the moves after `parent`, with the variations of each move before its
+    /// continuation
+    fn write_children(&self, parent: NodeId, tokens: &mut Vec<String>, force_number: bool) {
+        let Some((&main, variations)) = self.nodes[parent.0].children.split_first() else {
+            return;
+        };
+        self.write_move(main, tokens, force_number);
+        for &variation in variations {
+            tokens.push("(".to_owned());
+            self.write_move(variation, tokens, true);
+            self.write_children(variation, tokens, false);
+            tokens.push(")".to_owned());
+        }
+        // Black's move number is repeated after anything that interrupts the moves
+        let interrupted = !variations.is_empty() || self.comment_text(main).is_some();
+        self.write_children(main, tokens, interrupted);
+    }
+
+    fn write_move(&self, node: NodeId, tokens: &mut Vec<String>, force_number: bool) {
+        let (number, color) = self.move_number(node);
+        match color {
+            Color::White => tokens.push(format!("{number}.")),
+            Color::Black if force_number => tokens.push(format!("{number}...")),
+            Color::Black => (),
+        }
+        let n = &self.nodes[node.0];
+        tokens.push(n.san.clone());
+        tokens.extend(n.nags.iter().map(|nag| format!("${nag}")));
+        tokens.extend(self.comment_text(node));
+    }
+
+    /// The comment of a node in braces, with its clock time first
+    fn comment_text(&self, node: NodeId) -> Option<String> {
+        let n = &self.nodes[node.0];
+        let clock = n.clock.map(|clock| {
+            let secs = clock.as_secs();
+            let tenths = clock.subsec_millis() / 100;
+            let mut clk = format!(
+                "[%clk {}:{:02}:{:02}",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60
+            );
+            if tenths != 0 {
+                clk += &format!(".{tenths}");
+            }
+            clk + "]"
+        });
+        let text = match (clock, n.comment.as_deref()) {
+            (None, None) => return None,
+            (Some(clock), None) => clock,
+            (None, Some(comment)) => comment.to_owned(),
+            (Some(clock), Some(comment)) => format!("{clock} {comment}"),
+        };
+        Some(format!("{{{text}}}"))
+    }
+
+    /// Reads a game from Portable Game Notation (PGN). Tags, variations, comments, `[%clk]`
+    /// clock times, NAGs and move suffixes like `!?` are kept, and every move is checked to be
+    /// legal. A `Variant` tag sets the [Variant] and a `FEN` tag sets the starting position. Only
+    /// the first game in the text is read, and the game is left at its start.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if a move is improperly formatted or illegal, the `Variant` tag
+    /// names an unknown variant, the `FEN` tag is invalid, or the parentheses of the variations
+    /// don't match.
+    ///
+    /// # Examples
+    /// ```
+    /// # use chb_chess::Game;
+    /// let pgn = "[White \"Alice\"]\n\n1. e4 {[%clk 0:05:00]} e5 (1... c5 $1) 2. Nf3!? *";
+    /// let mut game = Game::from_pgn(pgn)?;
+    ///
+    /// assert_eq!(game.tag("White"), Some("Alice"));
+    /// assert_eq!(game.mainline().len(), 3);
+    /// while game.forward() {}
+    /// assert_eq!(game.nags(), &[5]);
+    /// # Ok::<(), chb_chess::BoardError>(())
+    /// ```
+    pub fn from_pgn(pgn: &str) -> Result<Self, BoardError> {
+        let clock_re = Regex::new(r"\[%clk\s+(\d+):(\d+):(\d+)(?:\.(\d+))?\]")
+            .expect("Invalid Regex for clock times");
+        let number_re = Regex::new(r"^\d+(\.+|$)").expect("Invalid Regex for move numbers");
+
+        let (tags, movetext) = Tags::read(pgn);
+        let variant = tags.get("Variant").map(str::parse::<Variant>).transpose()?;
+        let builder = match tags.get("FEN") {
+            Some(fen) => {
+                let mut builder = BoardBuilder::from_fen(fen)?;
+                if let Some(variant) = variant {
+                    builder.variant(variant);
+                }
+                builder
+            }
+            None => BoardBuilder::for_variant(variant.unwrap_or_default()),
+        };
+        let mut game = Self::from_board(builder.build()?);
+        game.tags = tags;
+
+        let mut variations = Vec::new();
+        // A comment at the start of a variation is kept for its first move
+        let mut pending: Option<(Option<Duration>, String)> = None;
+        let mut chars = movetext.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' => {
+                    let end = movetext[i..]
+                        .find('}')
+                        .ok_or(NotationError::UnterminatedComment)?;
+                    let mut comment = movetext[i + 1..i + end].to_owned();
+                    let mut clock = None;
+                    if let Some(caps) = clock_re.captures(&comment) {
+                        let field = |n: usize| caps[n].parse::<u64>().unwrap_or_default();
+                        let mut time =
+                            Duration::from_secs(field(1) * 3600 + field(2) * 60 + field(3));
+                        if let Some(fraction) = caps.get(4) {
+                            let digits = &fraction.as_str()[..fraction.as_str().len().min(3)];
+                            let millis = digits.parse::<u64>().unwrap_or_default()
+                                * 10u64.pow(3 - digits.len() as u32);
+                            time += Duration::from_millis(millis);
+                        }
+                        clock = Some(time);
+                        comment = comment.replace(&caps[0], "");
+                    }
+                    let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                    match &mut pending {
+                        Some((pending_clock, text)) => {
+                            *pending_clock = clock.or(*pending_clock);
+                            *text = join_comments(text, &comment);
+                        }
+                        None => annotate(&mut game, clock, &comment),
+                    }
+                    while chars.next_if(|(j, _)| *j <= i + end).is_some() {}
+                }
+                ';' => while chars.next_if(|(_, c)| *c != '\n').is_some() {},
+                '(' => {
+                    pending = Some((None, String::new()));
+                    variations.push(game.current());
+                    if !game.back() {
+                        return Err(NotationError::VariationBeforeFirstMove.into());
+                    }
+                }
+                ')' => {
+                    if let Some((clock, comment)) = pending.take() {
+                        annotate(&mut game, clock, &comment);
+                    }
+                    let node = variations
+                        .pop()
+                        .ok_or(NotationError::UnmatchedParenthesis)?;
+                    game.go_to(node);
+                }
+                _ if c.is_whitespace() => (),
+                _ => {
+                    let mut end = i + c.len_utf8();
+                    while let Some((j, c)) =
+                        chars.next_if(|(_, c)| !c.is_whitespace() && !"{}();".contains(*c))
+                    {
+                        end = j + c.len_utf8();
+                    }
+                    let token = &movetext[i..end];
+
+                    if let Some(nag) = token.strip_prefix('$') {
+                        let nag = nag
+                            .parse()
+                            .map_err(|_| NotationError::InvalidNag(token.to_owned()))?;
+                        game.add_nag(nag);
+                        continue;
+                    }
+                    if ["1-0", "0-1", "1/2-1/2", "*"].contains(&token) {
+                        if game.tag("Result").is_none() {
+                            game.set_tag("Result", token);
+                        }
+                        break;
+                    }
+
+                    let san = number_re.replace(token, "");
+                    if san.is_empty() {
+                        continue;
+                    }
+                    let suffix = SUFFIX_NAGS.iter().find(|(s, _)| san.ends_with(s));
+                    let mv = game.board.parse_san(&san)?;
+                    game.make(mv)?;
+                    if let Some((clock, comment)) = pending.take() {
+                        annotate(&mut game, clock, &comment);
+                    }
+                    if let Some((_, nag)) = suffix {
+                        game.add_nag(*nag);
+                    }
+                }
+            }
+        }
+        if !variations.is_empty() {
+            return Err(NotationError::UnclosedVariation.into());
+        }
+        while game.back() {}
+        Ok(game)
+    }
+}
+
+/// Adds a clock time and comment read from PGN to the current move, after any comment it
+/// already has
+fn annotate(game: &mut Game, clock: Option<Duration>, comment: &str) {
+    if let Some(clock) = clock {
+        game.set_clock(clock);
+    }
+    let comment = join_comments(game.comment().unwrap_or_default(), comment);
+    if !comment.is_empty() {
+        game.set_comment(&comment);
+    }
+}
+
+fn join_comments(first: &str, second: &str) -> String {
+    match (first.is_empty(), second.is_empty()) {
+        (true, _) => second.to_owned(),
+        (_, true) => first.to_owned(),
+        _ => format!("{first} {second}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Game, Variant};
+
+    #[test]
+    fn test_pgn_round_trip() {
+        let pgn = "[Event \"Casual\"]
+[White \"Alice\"]
+[Black \"Bob\"]
+[Result \"1-0\"]
+
+{An old trap} 1. e4 {[%clk 0:04:58.5] Best by test} 1... e5 2. Bc4 Nc6 (2... Nf6
+3. d3 (3. Qf3 d5 4. exd5) 3... Bc5) 3. Qh5 $1 Nf6 $4 4. Qxf7# 1-0
+";
+        let mut game = Game::from_pgn(pgn).unwrap();
+        assert_eq!(game.to_pgn(), pgn);
+        assert_eq!(game.comment(), Some("An old trap"));
+
+        game.forward();
+        assert_eq!(game.clock(), Some(Duration::from_millis(298_500)));
+        assert_eq!(game.comment(), Some("Best by test"));
+        for _ in 0..5 {
+            game.forward();
+        }
+        assert_eq!(game.nags(), &[4]);
+        assert!(game.forward());
+        assert!(game.board().outcome().is_some());
+    }
+
+    #[test]
+    fn test_pgn_from_position() {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+        let pgn = format!("[FEN \"{fen}\"]\n\n12... Kd7 13. e4 Ke6 *");
+        let game = Game::from_pgn(&pgn).unwrap();
+        assert_eq!(game.start().to_fen(), fen);
+        assert_eq!(
+            game.to_pgn(),
+            format!("[SetUp \"1\"]\n[FEN \"{fen}\"]\n[Result \"*\"]\n\n12... Kd7 13. e4 Ke6 *\n")
+        );
+
+        assert!(Game::from_pgn("1. e4 (e5) *").is_err());
+        assert!(Game::from_pgn("1 e4 e5 2 Nf3 *").is_ok());
+        assert!(Game::from_pgn("1. e4 e5 (1... c5 *").is_err());
+        assert!(Game::from_pgn("1. e5 *").is_err());
+    }
+
+    #[test]
+    fn test_pgn_variant() {
+        let pgn = "[Variant \"Horde\"]\n[Result \"*\"]\n\n1. d5 e6 2. dxe6 *\n";
+        let game = Game::from_pgn(pgn).unwrap();
+        assert_eq!(game.start().variant(), Variant::Horde);
+        assert_eq!(game.to_pgn(), pgn);
+
+        // The variant is written in its own tag rather than in the FEN
+        let fen = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+        let game = Game::from_pgn(&format!("[FEN \"{fen} racingkings\"]\n\n1. Nd4 *")).unwrap();
+        assert_eq!(game.start().variant(), Variant::RacingKings);
+        assert_eq!(
+            game.to_pgn(),
+            "[Variant \"Racing Kings\"]\n[Result \"*\"]\n\n1. Nd4 *\n"
+        );
+
+        assert!(Game::from_pgn("[Variant \"Crazyhouse\"]\n\n1. e4 *").is_err());
+    }
+
+    #[test]
+    fn test_pgn_movetext() {
+        // Zero castling isn't mistaken for a move number
+        let pgn = "1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 Nf6 5. d3 0-0 *";
+        let game = Game::from_pgn(pgn).unwrap();
+        assert_eq!(game.mainline().len(), 10);
+        assert_eq!(game.mainline()[6], "e1g1".parse().unwrap());
+
+        // A comment opening a variation belongs to its first move, not the move it replaces
+        let mut game = Game::from_pgn("1. e4 ({Alt} 1. d4) e5 *").unwrap();
+        game.forward();
+        assert_eq!(game.comment(), None);
+        game.back();
+        assert!(game.enter_variation(1));
+        assert_eq!(game.comment(), Some("Alt"));
+    }
+}
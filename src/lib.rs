@@ -1,5 +1,12 @@
 mod board;
-pub use board::{Board, builder::BoardBuilder};
+pub use board::{
+    builder::{BoardBuilder, FenNormalization, FenOptions, Severity, ValidationIssue},
+    fog::FogView,
+    hash::{ZobristHash, ZobristHash128, ZobristKeys, DEFAULT_SEED, ZOBRIST_KEY_COUNT},
+    position::Position,
+    premove::{PremoveOutcome, PremoveQueue},
+    Board,
+};
 
 mod ray;
 pub use ray::Ray;
@@ -15,18 +22,41 @@ mod piece;
 pub use piece::{Color, Piece, PieceKind, PROMO_PIECES};
 
 mod error;
-pub use error::{BoardError, ErrorKind};
+pub use error::{
+    BoardError, FenError, FenField, MoveError, NotationError, ParseError, PositionError,
+};
 
 mod dir; //Does dir need to be public?
 pub use dir::{Dir, ALL_DIRS};
 
 mod bitboard;
-pub use bitboard::{Bitboard, ALL, EMPTY, NOT_A_FILE, NOT_H_FILE};
+pub use bitboard::{Bitboard, ALL, DARK_SQUARES, EMPTY, LIGHT_SQUARES, NOT_A_FILE, NOT_H_FILE};
 
 mod check;
-pub use check::Check;
+pub use check::{Check, CheckKind};
 
 mod castle;
 pub use castle::{Castle};
 
 pub mod move_gen;
+
+mod variant;
+pub use variant::Variant;
+
+mod outcome;
+pub use outcome::{DrawReason, Outcome, WinReason};
+
+mod clock;
+pub use clock::{Clock, Increment, Period, TimeControl};
+
+mod bughouse;
+pub use bughouse::{BoardId, Bughouse, BughouseOutcome, Pocket};
+
+mod game;
+pub use game::{Game, NodeId};
+
+mod epd;
+pub use epd::{Epd, Operand, Operation};
+
+mod suite;
+pub use suite::{PositionReport, SearchLimit, SearchResult, Searcher, SuiteReport, TestSuite};
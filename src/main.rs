@@ -1,6 +1,6 @@
-use std::{io, env};
+use std::{error::Error, io, env};
 
-use chb_chess::{Board, BoardError, Move};
+use chb_chess::{Board, Move};
 
 fn main() -> io::Result<()> {
     let mut chess = Board::default();
@@ -26,7 +26,7 @@ fn main() -> io::Result<()> {
     }
 }
 
-fn handle_command(chess: &mut Board, cmd: &str, arg: &str) -> Result<(), BoardError> {
+fn handle_command(chess: &mut Board, cmd: &str, arg: &str) -> Result<(), Box<dyn Error>> {
     match cmd.to_lowercase().as_str() {
         "make" => {
             let mv: Move = arg.trim().parse()?;
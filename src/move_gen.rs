@@ -1,10 +1,11 @@
 use crate::{
     dir::{BISHOP_DIRS, ROOK_DIRS},
-    piece::PROMO_PIECES,
     Bitboard, Board, Check, Color, Dir, Move, Piece, PieceKind, Ray, Square, ALL, ALL_DIRS, EMPTY,
     NOT_A_FILE, NOT_H_FILE,
 };
 
+const BACK_RANKS: Bitboard = Bitboard::new(0xff000000000000ff);
+
 /// Use this function to get a list of all legal moves in the given [Board].
 /// It might be more convenient to use the [Board] moves method instead.
 ///
@@ -22,44 +23,75 @@ use crate::{
 /// ```
 #[inline]
 pub fn legal(board: &Board) -> Vec<Move> {
+    let mut mv_list = if board.variant().has_check() {
+        royal(board)
+    } else {
+        non_royal(board)
+    };
+    board.variant().filter_moves(board, &mut mv_list);
+    mv_list
+}
+
+#[inline(always)]
+fn royal(board: &Board) -> Vec<Move> {
     let mut mv_list = Vec::with_capacity(50);
     // Fill in moves
-    pawn_moves(
-        board,
-        &mut mv_list,
-        board[Piece::Filled(PieceKind::Pawn, board.color_to_move())],
-        board.color_to_move(),
-    );
-    knight_moves(
-        board,
+    piece_moves(board, &mut mv_list, board.color_to_move());
+    king_moves(board, &mut mv_list, board.color_to_move());
+    filter_moves_by_check(board, &mut mv_list, board.color_to_move());
+    mv_list
+}
+
+/// Without check, every pseudo-legal move is legal
+#[inline(always)]
+fn non_royal(board: &Board) -> Vec<Move> {
+    pseudo_legal(board)
+}
+
+/// Generates every move that follows the movement rules of the pieces for the side to move,
+/// without checking whether it leaves the king attacked. Pins and attacked squares are still
+/// respected if the board has them.
+#[inline(always)]
+pub(crate) fn pseudo_legal(board: &Board) -> Vec<Move> {
+    let color = board.color_to_move();
+    let mut mv_list = Vec::with_capacity(50);
+    piece_moves(board, &mut mv_list, color);
+    // The king is an ordinary piece, so it may step onto attacked squares
+    king_steps(
         &mut mv_list,
-        board[Piece::Filled(PieceKind::Knight, board.color_to_move())],
-        board.color_to_move(),
+        board[Piece::king(color)],
+        board[Piece::Empty] | board[!color],
     );
+    castling_moves(board, &mut mv_list, color);
+    mv_list
+}
+
+/// Fills in the moves for every piece of the given color other than the king
+#[inline(always)]
+fn piece_moves(board: &Board, mv_list: &mut Vec<Move>, color: Color) {
+    pawn_moves(board, mv_list, board[Piece::pawn(color)], color);
+    knight_moves(board, mv_list, board[Piece::knight(color)], color);
     sliding_moves(
         board,
-        &mut mv_list,
-        board[Piece::Filled(PieceKind::Bishop, board.color_to_move())],
-        board.color_to_move(),
+        mv_list,
+        board[Piece::bishop(color)],
+        color,
         PieceKind::Bishop,
     );
     sliding_moves(
         board,
-        &mut mv_list,
-        board[Piece::Filled(PieceKind::Rook, board.color_to_move())],
-        board.color_to_move(),
+        mv_list,
+        board[Piece::rook(color)],
+        color,
         PieceKind::Rook,
     );
     sliding_moves(
         board,
-        &mut mv_list,
-        board[Piece::Filled(PieceKind::Queen, board.color_to_move())],
-        board.color_to_move(),
+        mv_list,
+        board[Piece::queen(color)],
+        color,
         PieceKind::Queen,
     );
-    king_moves(board, &mut mv_list, board.color_to_move());
-    filter_moves_by_check(board, &mut mv_list, board.color_to_move());
-    mv_list
 }
 
 /// Use this function to create a list of all legal moves originating
@@ -79,6 +111,14 @@ pub fn legal(board: &Board) -> Vec<Move> {
 ///
 /// # Ok::<(), BoardError>(())
 pub fn for_square(board: &Board, sqr: Square) -> Vec<Move> {
+    if board.variant().has_forced_captures() || !board.variant().has_check() {
+        // Whether a move is legal depends on every other piece's captures, and a king that isn't
+        // royal moves like any other piece
+        return legal(board)
+            .into_iter()
+            .filter(|mv| mv.origin == sqr)
+            .collect();
+    }
     let mut move_list = Vec::with_capacity(21);
     // Fill in moves
     if let Piece::Filled(kind, color) = board[sqr] {
@@ -98,9 +138,55 @@ pub fn for_square(board: &Board, sqr: Square) -> Vec<Move> {
         }
     }
     filter_moves_by_check(board, &mut move_list, board.color_to_move());
+    board.variant().filter_moves(board, &mut move_list);
     move_list
 }
 
+/// Use this function to get the legal drops for the side to move, as in Bughouse where captured
+/// pieces can be placed back on the board. Only the given kinds of pieces are dropped, so pass
+/// the kinds the player has in hand.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{move_gen, Board, PieceKind};
+/// let mut board = Board::default();
+/// board.make("g1f3".parse()?)?;
+/// board.make("g8f6".parse()?)?;
+///
+/// // Pawns can't be dropped on the first or last rank
+/// assert_eq!(move_gen::drops(&board, &[PieceKind::Knight]).len(), 32);
+/// assert_eq!(move_gen::drops(&board, &[PieceKind::Pawn]).len(), 30);
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+pub fn drops(board: &Board, kinds: &[PieceKind]) -> Vec<Move> {
+    let color = board.color_to_move();
+    let targets = board[Piece::Empty]
+        & match board.check() {
+            Check::None => ALL,
+            // Dropping can only block a check, not capture the checking piece
+            Check::Single(sqr) => Bitboard::between(
+                board
+                    .king(color)
+                    .expect("King must be on the board to be in check"),
+                sqr,
+            ),
+            Check::Double => EMPTY,
+        };
+
+    let mut mv_list = Vec::new();
+    for kind in kinds {
+        let targets = if *kind == PieceKind::Pawn {
+            targets & !BACK_RANKS
+        } else {
+            targets
+        };
+        for dest in targets {
+            mv_list.push(Move::drop(Piece::Filled(*kind, color), dest));
+        }
+    }
+    mv_list
+}
+
 #[inline(always)]
 fn filter_moves_by_check(board: &Board, mvs: &mut Vec<Move>, color: Color) {
     let ep_pawn = if let Some(sq) = board.ep_target() {
@@ -116,7 +202,10 @@ fn filter_moves_by_check(board: &Board, mvs: &mut Vec<Move>, color: Color) {
     let check_limits = match board.check() {
         Check::None => ALL,
         Check::Single(sqr) => {
-            Bitboard::between(board.king(board.color_to_move()), sqr) | sqr.into()
+            let king = board
+                .king(board.color_to_move())
+                .expect("King must be on the board to be in check");
+            Bitboard::between(king, sqr) | sqr.into()
         }
         Check::Double => EMPTY,
     };
@@ -132,21 +221,20 @@ fn filter_moves_by_check(board: &Board, mvs: &mut Vec<Move>, color: Color) {
 
 #[inline(always)]
 fn king_moves(board: &Board, mvs: &mut Vec<Move>, color: Color) {
-    let origin = board.king(color);
+    let Some(origin) = board.king(color) else {
+        return;
+    };
     let free = (board[Piece::Empty] | board[!color]) & !board.attacks();
 
-    for dir in ALL_DIRS {
-        if let Some(dest) = origin.checked_add(dir) {
-            if free.contains(dest) {
-                mvs.push(Move {
-                    origin,
-                    dest,
-                    promotion: Piece::Empty,
-                })
-            }
-        }
-    }
+    king_steps(mvs, origin.into(), free);
+    castling_moves(board, mvs, color);
+}
 
+#[inline(always)]
+fn castling_moves(board: &Board, mvs: &mut Vec<Move>, color: Color) {
+    let Some(origin) = board.king(color) else {
+        return;
+    };
     if able_to_castle_kingside(board, color) {
         let dest = origin
             .checked_add(Dir::East)
@@ -173,6 +261,23 @@ fn king_moves(board: &Board, mvs: &mut Vec<Move>, color: Color) {
     }
 }
 
+#[inline(always)]
+fn king_steps(mvs: &mut Vec<Move>, initial: Bitboard, free: Bitboard) {
+    for origin in initial {
+        for dir in ALL_DIRS {
+            if let Some(dest) = origin.checked_add(dir) {
+                if free.contains(dest) {
+                    mvs.push(Move {
+                        origin,
+                        dest,
+                        promotion: Piece::Empty,
+                    })
+                }
+            }
+        }
+    }
+}
+
 #[inline(always)]
 fn able_to_castle_kingside(board: &Board, color: Color) -> bool {
     let filter_offset = if color == Color::White { 56 } else { 0 };
@@ -181,7 +286,7 @@ fn able_to_castle_kingside(board: &Board, color: Color) -> bool {
 
     board.castle(color).get_king_side()
         && board[Piece::Empty] & ks_filter == ks_filter
-        && (board.attacks() & ks_check).is_empty()
+        && (!board.variant().has_check() || (board.attacks() & ks_check).is_empty())
 }
 
 #[inline(always)]
@@ -192,14 +297,16 @@ fn able_to_castle_queenside(board: &Board, color: Color) -> bool {
 
     board.castle(color).get_queen_side()
         && board[Piece::Empty] & qs_filter == qs_filter
-        && (board.attacks() & qs_check).is_empty()
+        && (!board.variant().has_check() || (board.attacks() & qs_check).is_empty())
 }
 
 #[inline(always)]
 fn pawn_moves(board: &Board, mvs: &mut Vec<Move>, initial: Bitboard, color: Color) {
-    let promotions: Vec<Piece> = PROMO_PIECES
-        .into_iter()
-        .map(|kind| Piece::Filled(kind, color))
+    let promotions: Vec<Piece> = board
+        .variant()
+        .promotion_kinds()
+        .iter()
+        .map(|kind| Piece::Filled(*kind, color))
         .collect();
 
     let dir = if color == Color::White {
@@ -207,13 +314,13 @@ fn pawn_moves(board: &Board, mvs: &mut Vec<Move>, initial: Bitboard, color: Colo
     } else {
         Dir::South
     };
-    let dp_free = board[Piece::Empty]
-        & (board[Piece::Empty] << dir)
-        & if color == Color::White {
-            Bitboard::new(0xff00000000u64)
-        } else {
-            Bitboard::new(0xff000000u64)
-        };
+    let dp_ranks = match (color, board.variant().has_first_rank_double_push()) {
+        (Color::White, false) => Bitboard::new(0xff00000000u64),
+        (Color::White, true) => Bitboard::new(0xffff00000000u64),
+        // Only White plays the horde, so Black pawns double push as in standard chess
+        (Color::Black, _) => Bitboard::new(0xff000000u64),
+    };
+    let dp_free = board[Piece::Empty] & (board[Piece::Empty] << dir) & dp_ranks;
     let cap = board[!color]
         | if !ep_is_pinned(board) {
             board
@@ -275,12 +382,17 @@ fn ep_is_pinned(board: &Board) -> bool {
     let Some(ep_sq) = board.ep_target() else {
         return true;
     };
+    if !board.variant().has_check() {
+        return false;
+    }
     let (color, dir) = if ep_sq.rank() == 2 {
         (Color::White, Dir::South)
     } else {
         (Color::Black, Dir::North)
     };
-    let king = board.king(color);
+    let Some(king) = board.king(color) else {
+        return false;
+    };
     let ep_pawn = ep_sq.checked_add(dir).expect("Invalid En Passant Square");
     let Some(ray) = Ray::from(king, ep_pawn) else {
         return false;
@@ -362,7 +474,10 @@ fn sliding_moves(
     }
 
     for origin in pinned_pieces {
-        let ray = Ray::from(board.king(color), origin)
+        let king = board
+            .king(color)
+            .expect("If piece is pinned, its king must be on the board");
+        let ray = Ray::from(king, origin)
             .expect("If piece is pinned, it must be in a line with the king");
         if ray.dir.piece_kind() == kind || kind == PieceKind::Queen {
             for dest in ray {
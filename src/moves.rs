@@ -3,7 +3,9 @@ use std::{fmt, str};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::{BoardError, Castle, ErrorKind, Piece, Square};
+use crate::{
+    board::hash::StoredHash, squares, BoardError, Castle, ParseError, Piece, PieceKind, Square,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -15,6 +17,13 @@ pub struct Move {
 
 impl fmt::Display for Move {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_null() {
+            return write!(f, "0000");
+        }
+        if self.is_drop() {
+            let kind = self.promotion.to_string().to_uppercase();
+            return write!(f, "{}@{}", kind, self.dest);
+        }
         let mut output = format!("{}{}", self.origin, self.dest);
         if self.promotion != Piece::Empty {
             output += self.promotion.to_string().to_lowercase().as_str();
@@ -26,15 +35,40 @@ impl fmt::Display for Move {
 impl str::FromStr for Move {
     type Err = BoardError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "0000" {
+            return Ok(Move::NULL);
+        }
+        let get_err = || BoardError::from(ParseError::Move(s.to_owned()));
+        if s.len() == 4 && s.get(1..2) == Some("@") {
+            // Drops use the case of the piece for its color, so `P@e4` is a white pawn
+            let piece: Piece = s
+                .get(0..1)
+                .unwrap_or_default()
+                .parse()
+                .map_err(|_| get_err())?;
+            let dest: Square = s
+                .get(2..4)
+                .unwrap_or_default()
+                .parse()
+                .map_err(|_| get_err())?;
+            if piece.is_kind(PieceKind::King) {
+                return Err(get_err());
+            }
+            return Ok(Move::drop(piece, dest));
+        }
         if (s.len() != 5 && s.len() != 4) || !s.is_ascii() {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Improperly formatted move",
-            ));
+            return Err(get_err());
         }
-        let get_err = || BoardError::new(ErrorKind::InvalidInput, "Improperly formatted move");
-        let origin: Square = s.get(0..2).ok_or_else(get_err)?.parse()?;
-        let dest: Square = s.get(2..4).ok_or_else(get_err)?.parse()?;
+        let origin: Square = s
+            .get(0..2)
+            .ok_or_else(get_err)?
+            .parse()
+            .map_err(|_| get_err())?;
+        let dest: Square = s
+            .get(2..4)
+            .ok_or_else(get_err)?
+            .parse()
+            .map_err(|_| get_err())?;
 
         let promotion = if let Some(promo) = s.get(4..5) {
             let white_promotion =
@@ -43,10 +77,7 @@ impl str::FromStr for Move {
                 s.get(1..2).ok_or_else(get_err)? == "2" && s.get(3..4).ok_or_else(get_err)? == "1";
             if !["k", "q", "b", "n", "r"].contains(&promo) || !(white_promotion || black_promotion)
             {
-                return Err(BoardError::new(
-                    ErrorKind::InvalidInput,
-                    "Improperly formatted move",
-                ));
+                return Err(get_err());
             }
             let promo_case = if white_promotion {
                 promo.to_uppercase()
@@ -65,6 +96,37 @@ impl str::FromStr for Move {
     }
 }
 
+impl Move {
+    /// The null move, which passes the turn without moving anything. It is written `0000`, as in
+    /// UCI, and stored with the same origin and destination and no piece.
+    pub const NULL: Move = Move {
+        origin: squares::A1,
+        dest: squares::A1,
+        promotion: Piece::Empty,
+    };
+
+    /// Creates a move that places a [Piece] on an empty [Square] instead of moving one that is
+    /// already on the board, as in Bughouse. A drop is stored with the same origin and
+    /// destination, and the dropped piece in place of the promotion.
+    pub fn drop(piece: Piece, square: Square) -> Move {
+        Move {
+            origin: square,
+            dest: square,
+            promotion: piece,
+        }
+    }
+
+    /// Returns true if this move places a piece rather than moving one
+    pub fn is_drop(&self) -> bool {
+        self.origin == self.dest && self.promotion != Piece::Empty
+    }
+
+    /// Returns true if this is the [Move::NULL] move
+    pub fn is_null(&self) -> bool {
+        self.origin == self.dest && self.promotion == Piece::Empty
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MoveState {
@@ -73,4 +135,6 @@ pub struct MoveState {
     pub castle: [Castle; 2],
     pub halfmove: u32,
     pub ep_target: Option<Square>,
+    /// The hash of the position before the move
+    pub hash: StoredHash,
 }
@@ -0,0 +1,74 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Color;
+
+/// The result of a finished game.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Outcome {
+    Win { winner: Color, reason: WinReason },
+    Draw(DrawReason),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum WinReason {
+    Checkmate,
+    /// The winner has no legal moves left (Antichess)
+    OutOfMoves,
+    /// The winner has no pieces left (Antichess)
+    OutOfPieces,
+    /// The loser has no pieces left (Horde)
+    AllPiecesCaptured,
+    /// The winner's king reached the eighth rank first (Racing Kings)
+    KingReachedGoal,
+    /// The loser's king was captured (Fog of War)
+    KingCaptured,
+    /// The loser ran out of time on their clock
+    Timeout,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DrawReason {
+    Stalemate,
+    /// Neither side has the material left to checkmate
+    InsufficientMaterial,
+    /// 75 moves by each side without a capture or pawn move, which ends the game automatically
+    SeventyFiveMoveRule,
+    /// The same position occurred five times, which ends the game automatically
+    FivefoldRepetition,
+    /// 50 moves by each side without a capture or pawn move, which a player may claim
+    FiftyMoveRule,
+    /// The same position occurred three times, which a player may claim
+    ThreefoldRepetition,
+    /// Both kings reached the eighth rank on consecutive moves (Racing Kings)
+    KingsReachedGoal,
+    /// A player ran out of time, but their opponent has no way to checkmate them
+    TimeoutVsInsufficientMaterial,
+}
+
+impl DrawReason {
+    /// Returns true if a player has to claim this draw, rather than it ending the game on its own
+    pub fn is_claimable(&self) -> bool {
+        matches!(
+            self,
+            DrawReason::FiftyMoveRule | DrawReason::ThreefoldRepetition
+        )
+    }
+}
+
+impl Outcome {
+    /// Returns the winning [Color], or `None` if the game was drawn
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            Outcome::Win { winner, .. } => Some(*winner),
+            Outcome::Draw(_) => None,
+        }
+    }
+
+    pub fn is_draw(&self) -> bool {
+        matches!(self, Outcome::Draw(_))
+    }
+}
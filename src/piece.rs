@@ -2,7 +2,7 @@
 use serde::{Deserialize, Serialize};
 use std::{fmt, ops, str};
 
-use crate::error::{BoardError, ErrorKind};
+use crate::error::{BoardError, ParseError};
 
 pub const PROMO_PIECES: [PieceKind; 4] = [
     PieceKind::Queen,
@@ -55,7 +55,10 @@ impl str::FromStr for Piece {
     type Err = BoardError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Piece::try_from(s.parse::<char>()?)
+        let piece = s
+            .parse::<char>()
+            .map_err(|_| ParseError::Piece(s.to_owned()))?;
+        Piece::try_from(piece)
     }
 }
 
@@ -76,12 +79,7 @@ impl TryFrom<char> for Piece {
             'r' => Piece::rook(color),
             'p' => Piece::pawn(color),
             '-' => Piece::Empty,
-            _ => {
-                return Err(BoardError::new(
-                    ErrorKind::InvalidInput,
-                    "Illegal character for piece",
-                ))
-            }
+            _ => return Err(ParseError::Piece(c.to_string()).into()),
         })
     }
 }
@@ -91,10 +89,7 @@ impl TryFrom<usize> for Piece {
 
     fn try_from(index: usize) -> Result<Self, Self::Error> {
         if index > 12 {
-            return Err(BoardError::new(
-                ErrorKind::OutOfBounds,
-                "Index out of bounds for piece",
-            ));
+            return Err(ParseError::PieceIndex(index).into());
         }
         let color = if (index & 1) == 1 {
             Color::Black
@@ -145,10 +140,7 @@ impl str::FromStr for Color {
         match s {
             "w" => Ok(Self::White),
             "b" => Ok(Self::Black),
-            _ => Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Attempted to parse color from invalid string",
-            )),
+            _ => Err(ParseError::Color(s.to_owned()).into()),
         }
     }
 }
@@ -2,7 +2,7 @@
 use serde::{Deserialize, Serialize};
 use std::{fmt, str};
 
-use crate::{BoardError, Dir, ErrorKind, Bitboard};
+use crate::{BoardError, Dir, ParseError, Bitboard};
 
 const A1: u8 = 63;
 
@@ -99,10 +99,7 @@ impl str::FromStr for Square {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if !is_valid_square(s) {
-            return Err(BoardError::new(
-                ErrorKind::InvalidInput,
-                "Invalid position format",
-            ));
+            return Err(ParseError::Square(s.to_owned()).into());
         }
         let p_bytes = s.as_bytes();
         Ok(Square((b'h' - p_bytes[0]) + ((b'8' - p_bytes[1]) << 3)))
@@ -113,10 +110,7 @@ impl TryFrom<u8> for Square {
     type Error = BoardError;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
         if value > A1 {
-            return Err(BoardError::new(
-                ErrorKind::OutOfBounds,
-                "Index out of the board",
-            ));
+            return Err(ParseError::SquareIndex(value.into()).into());
         }
 
         Ok(Square(value))
@@ -126,36 +120,27 @@ impl TryFrom<usize> for Square {
     type Error = BoardError;
     fn try_from(value: usize) -> Result<Self, Self::Error> {
         if value > A1.into() {
-            return Err(BoardError::new(
-                ErrorKind::OutOfBounds,
-                "Index out of the board",
-            ));
+            return Err(ParseError::SquareIndex(value as u64).into());
         }
-        Ok(Square(value.try_into()?))
+        Ok(Square(value as u8))
     }
 }
 impl TryFrom<u32> for Square {
     type Error = BoardError;
     fn try_from(value: u32) -> Result<Self, Self::Error> {
         if value > A1.into() {
-            return Err(BoardError::new(
-                ErrorKind::OutOfBounds,
-                "Index out of the board",
-            ));
+            return Err(ParseError::SquareIndex(value as u64).into());
         }
-        Ok(Square(value.try_into()?))
+        Ok(Square(value as u8))
     }
 }
 impl TryFrom<u64> for Square {
     type Error = BoardError;
     fn try_from(value: u64) -> Result<Self, Self::Error> {
         if value > A1.into() {
-            return Err(BoardError::new(
-                ErrorKind::OutOfBounds,
-                "Index out of the board",
-            ));
+            return Err(ParseError::SquareIndex(value).into());
         }
-        Ok(Square(value.try_into()?))
+        Ok(Square(value as u8))
     }
 }
 
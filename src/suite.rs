@@ -0,0 +1,250 @@
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{Board, BoardError, Epd, Move};
+
+/// How long a [Searcher] may search each position
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum SearchLimit {
+    Time(Duration),
+    /// A depth in plies
+    Depth(u32),
+    Nodes(u64),
+}
+
+/// The move a [Searcher] chose and the work it did to find it
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SearchResult {
+    pub best_move: Move,
+    pub nodes: u64,
+    /// The depth completed, in plies
+    pub depth: u32,
+}
+
+/// A search that a [TestSuite] can be run against. The crate has no engine of its own, so this
+/// is implemented for the engine being tested.
+pub trait Searcher {
+    /// Searches `board` within `limit` and returns the best move found. The board always has
+    /// at least one legal move.
+    fn search(&mut self, board: &Board, limit: SearchLimit) -> SearchResult;
+}
+
+/// A suite of test positions read from EPD, like WAC or ECM, each with a `bm` best move or an
+/// `am` move to avoid. Positions with neither are skipped.
+///
+/// # Examples
+/// ```
+/// # use chb_chess::{Board, SearchLimit, SearchResult, Searcher, TestSuite};
+/// /// Plays the first legal move it finds, which here is the only one
+/// struct FirstMove;
+///
+/// impl Searcher for FirstMove {
+///     fn search(&mut self, board: &Board, _: SearchLimit) -> SearchResult {
+///         SearchResult { best_move: board.legal_moves()[0], nodes: 1, depth: 1 }
+///     }
+/// }
+///
+/// let suite = TestSuite::parse(
+///     r#"k7/8/8/8/8/8/1r6/K7 w - - bm Kxb2; id "take";
+///     k7/8/8/8/8/8/1r6/K7 w - - am Kxb2; id "avoid";"#,
+/// )?;
+/// let report = suite.run(&mut FirstMove, SearchLimit::Depth(1));
+///
+/// assert_eq!(report.total(), 2);
+/// assert_eq!(report.solved(), 1);
+/// # Ok::<(), chb_chess::BoardError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestSuite {
+    positions: Vec<Epd>,
+}
+
+impl TestSuite {
+    /// Reads a suite with one EPD position per line, ignoring blank lines
+    ///
+    /// # Errors
+    ///
+    /// Returns a [BoardError] if a line isn't valid EPD.
+    pub fn parse(epd: &str) -> Result<Self, BoardError> {
+        let positions = epd
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { positions })
+    }
+
+    pub fn positions(&self) -> &[Epd] {
+        &self.positions
+    }
+
+    /// Searches every position with a `bm` or `am` operation, scoring it as solved if the move
+    /// found is one of the best moves and none of the moves to avoid
+    pub fn run(&self, searcher: &mut impl Searcher, limit: SearchLimit) -> SuiteReport {
+        let mut positions = Vec::new();
+        for (i, epd) in self.positions.iter().enumerate() {
+            let (best, avoid) = (epd.best_moves(), epd.avoid_moves());
+            if (best.is_empty() && avoid.is_empty()) || epd.board().legal_moves().is_empty() {
+                continue;
+            }
+
+            let start = Instant::now();
+            let result = searcher.search(epd.board(), limit);
+            let time = start.elapsed();
+
+            let mv = result.best_move;
+            let legal = epd.board().legal_moves().contains(&mv);
+            positions.push(PositionReport {
+                id: epd.id().map_or_else(|| (i + 1).to_string(), str::to_owned),
+                fen: epd.board().to_fen(),
+                san: match legal {
+                    true => epd.board().to_san(mv),
+                    false => mv.to_string(),
+                },
+                result,
+                solved: legal && (best.is_empty() || best.contains(&mv)) && !avoid.contains(&mv),
+                time,
+            });
+        }
+        SuiteReport { positions }
+    }
+}
+
+/// How a [Searcher] did on one position of a [TestSuite]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PositionReport {
+    /// The `id` of the position, or its number in the suite counting from 1 if it has none
+    pub id: String,
+    pub fen: String,
+    pub result: SearchResult,
+    /// The move found in SAN
+    pub san: String,
+    pub solved: bool,
+    pub time: Duration,
+}
+
+/// The results of running a [TestSuite]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SuiteReport {
+    pub positions: Vec<PositionReport>,
+}
+
+impl SuiteReport {
+    /// The number of positions searched
+    pub fn total(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn solved(&self) -> usize {
+        self.positions.iter().filter(|p| p.solved).count()
+    }
+
+    pub fn time(&self) -> Duration {
+        self.positions.iter().map(|p| p.time).sum()
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.positions.iter().map(|p| p.result.nodes).sum()
+    }
+
+    /// Writes the report as JSON, with times in microseconds:
+    ///
+    /// ```json
+    /// {"total":1,"solved":1,"time_us":1520,"nodes":4096,"positions":[{"id":"WAC.001",
+    /// "fen":"...","move":"Qg6","solved":true,"time_us":1520,"nodes":4096,"depth":6}]}
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            r#"{{"total":{},"solved":{},"time_us":{},"nodes":{},"positions":["#,
+            self.total(),
+            self.solved(),
+            self.time().as_micros(),
+            self.nodes()
+        );
+        for (i, p) in self.positions.iter().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            let _ = write!(
+                json,
+                r#"{{"id":{},"fen":{},"move":{},"solved":{},"time_us":{},"nodes":{},"depth":{}}}"#,
+                json_string(&p.id),
+                json_string(&p.fen),
+                json_string(&p.san),
+                p.solved,
+                p.time.as_micros(),
+                p.result.nodes,
+                p.result.depth
+            );
+        }
+        json + "]}"
+    }
+}
+
+/// Quotes a string for JSON
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, Check, SearchLimit, SearchResult, Searcher, TestSuite};
+
+    /// Finds mates in one, and otherwise plays the first legal move
+    struct MateInOne;
+
+    impl Searcher for MateInOne {
+        fn search(&mut self, board: &Board, _: SearchLimit) -> SearchResult {
+            let mut board = board.clone();
+            let mvs = board.legal_moves();
+            let mate = mvs.iter().copied().find(|mv| {
+                board.make(*mv).unwrap();
+                let mate = board.check() != Check::None && board.legal_moves().is_empty();
+                board.unmake();
+                mate
+            });
+            SearchResult {
+                best_move: mate.unwrap_or(mvs[0]),
+                nodes: mvs.len() as u64,
+                depth: 1,
+            }
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let suite = TestSuite::parse(
+            "6k1/5ppp/8/8/8/8/8/R5K1 w - - bm Ra8#; id \"back\\rank\";\n\
+            \n\
+            6k1/5ppp/8/8/8/8/8/R5K1 w - - am Ra8#;\n\
+            6k1/5ppp/8/8/8/8/8/R5K1 w - - c0 \"no moves to score\";",
+        )
+        .unwrap();
+        assert_eq!(suite.positions().len(), 3);
+
+        let report = suite.run(&mut MateInOne, SearchLimit::Nodes(100));
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.solved(), 1);
+        assert_eq!(report.nodes(), 2 * 17);
+        assert_eq!(report.positions[0].san, "Ra8#");
+        assert_eq!(report.positions[1].id, "2");
+
+        let json = report.to_json();
+        assert!(json.starts_with(r#"{"total":2,"solved":1,"time_us":"#));
+        assert!(json.contains(r#"{"id":"back\\rank","fen":"6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1","move":"Ra8#","solved":true,"#));
+    }
+}
@@ -0,0 +1,358 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{fmt, str};
+
+use crate::{
+    Board, BoardError, Check, Color, DrawReason, Move, Outcome, ParseError, Piece, PieceKind,
+    Square, WinReason, PROMO_PIECES,
+};
+
+mod antichess;
+mod fog_of_war;
+mod horde;
+mod racing_kings;
+
+const STANDARD_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+const ANTICHESS_PROMO_PIECES: [PieceKind; 5] = [
+    PieceKind::Queen,
+    PieceKind::Bishop,
+    PieceKind::Knight,
+    PieceKind::Rook,
+    PieceKind::King,
+];
+
+/// The set of rules a [Board] is played under.
+///
+/// Every rule that differs between variants is answered here, either as a property of the
+/// variant (like [Variant::has_castling]) or as a hook that the board, builder and move generator
+/// call into. That includes what making a move does that differs from standard chess
+/// ([Variant::ep_target_after]) and how the variant is written in a FEN. Adding a variant means
+/// adding it to this enum and its own submodule, and the compiler will point out every rule it
+/// still needs to define.
+///
+/// A FEN of a board of any variant but Standard names the variant in a seventh field after the
+/// counters, like `horde`, so that the board reads back as the same variant.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Variant {
+    #[default]
+    Standard,
+    /// Losing chess. Captures are compulsory, the king is an ordinary piece that can be captured
+    /// or promoted to, and a player with no pieces or no moves wins.
+    Antichess,
+    /// White has 36 pawns and no king, and pawns on the first rank may move two squares. Black
+    /// wins by capturing every white piece.
+    Horde,
+    /// Checks are not allowed, and the first king to reach the eighth rank wins. If black's king
+    /// can reach it on the move right after white's, the game is a draw.
+    RacingKings,
+    /// Dark chess. Each player only sees the squares their pieces can move to, so there is no
+    /// check and the game is won by capturing the king.
+    FogOfWar,
+}
+
+impl Variant {
+    /// Returns true if the king is royal in this variant, meaning it can be put in check and the
+    /// side to move may not leave it attacked.
+    #[inline(always)]
+    pub fn has_check(&self) -> bool {
+        match self {
+            Variant::Standard | Variant::Horde | Variant::RacingKings => true,
+            Variant::Antichess | Variant::FogOfWar => false,
+        }
+    }
+
+    /// Returns true if castling is part of this variant.
+    #[inline(always)]
+    pub fn has_castling(&self) -> bool {
+        match self {
+            Variant::Standard | Variant::Horde | Variant::FogOfWar => true,
+            Variant::Antichess | Variant::RacingKings => false,
+        }
+    }
+
+    /// Returns true if a player who is able to capture must do so.
+    #[inline(always)]
+    pub fn has_forced_captures(&self) -> bool {
+        match self {
+            Variant::Standard | Variant::Horde | Variant::RacingKings | Variant::FogOfWar => false,
+            Variant::Antichess => true,
+        }
+    }
+
+    /// Returns true if pawns on their first rank may move two squares.
+    #[inline(always)]
+    pub fn has_first_rank_double_push(&self) -> bool {
+        matches!(self, Variant::Horde)
+    }
+
+    /// Returns true if a move may put the opponent's king in check.
+    #[inline(always)]
+    pub fn allows_giving_check(&self) -> bool {
+        !matches!(self, Variant::RacingKings)
+    }
+
+    /// Returns true if the game is drawn once neither side has the material to checkmate, as
+    /// under FIDE rules. Other variants have their own ways of winning.
+    #[inline(always)]
+    pub fn has_material_draws(&self) -> bool {
+        matches!(self, Variant::Standard)
+    }
+
+    /// Returns true if pawns of the given [Color] may stand on their first rank, as the horde
+    /// starts with.
+    #[inline(always)]
+    pub fn allows_first_rank_pawns(&self, color: Color) -> bool {
+        matches!((self, color), (Variant::Horde, Color::White))
+    }
+
+    /// The number of kings the given [Color] must have, or `None` if it may have any number of
+    /// them.
+    #[inline(always)]
+    pub fn king_count(&self, color: Color) -> Option<u32> {
+        match (self, color) {
+            (Variant::Standard | Variant::RacingKings | Variant::FogOfWar, _)
+            | (Variant::Horde, Color::Black) => Some(1),
+            (Variant::Horde, Color::White) => Some(0),
+            (Variant::Antichess, _) => None,
+        }
+    }
+
+    /// The kinds of pieces a pawn may promote to
+    #[inline(always)]
+    pub fn promotion_kinds(&self) -> &'static [PieceKind] {
+        match self {
+            Variant::Standard | Variant::Horde | Variant::RacingKings | Variant::FogOfWar => {
+                &PROMO_PIECES
+            }
+            Variant::Antichess => &ANTICHESS_PROMO_PIECES,
+        }
+    }
+
+    /// The starting position of this variant in Forsynth-Edwards Notation (FEN)
+    pub fn starting_fen(&self) -> &'static str {
+        match self {
+            Variant::Standard | Variant::FogOfWar => STANDARD_FEN,
+            Variant::Antichess => antichess::STARTING_FEN,
+            Variant::Horde => horde::STARTING_FEN,
+            Variant::RacingKings => racing_kings::STARTING_FEN,
+        }
+    }
+
+    /// How many of the given piece its side starts with
+    pub(crate) fn starting_count(&self, piece: Piece) -> u32 {
+        let symbol = piece.to_string();
+        let placement = self.starting_fen().split(' ').next().unwrap_or_default();
+        placement.matches(symbol.as_str()).count() as u32
+    }
+
+    /// The en passant target a pawn leaves by moving from `origin` to `dest`, which is only set
+    /// by a double push. This is the effect of making a move that differs between variants: in
+    /// Horde, a double push from the first rank can't be captured en passant. Unmaking restores
+    /// the earlier target from the move history, so it needs no hook.
+    #[inline(always)]
+    pub(crate) fn ep_target_after(&self, origin: Square, dest: Square) -> Option<Square> {
+        if origin.index().abs_diff(dest.index()) != 16 {
+            return None;
+        }
+        match self {
+            Variant::Horde if origin.rank() == 0 || origin.rank() == 7 => None,
+            _ => Square::try_from(origin.index().max(dest.index()) - 8).ok(),
+        }
+    }
+
+    /// The name of the variant in a FEN, or `None` for Standard, whose FENs keep to the six
+    /// standard fields
+    fn fen_name(&self) -> Option<&'static str> {
+        match self {
+            Variant::Standard => None,
+            Variant::Antichess => Some("antichess"),
+            Variant::Horde => Some("horde"),
+            Variant::RacingKings => Some("racingkings"),
+            Variant::FogOfWar => Some("fogofwar"),
+        }
+    }
+
+    /// Writes the fields a FEN of this variant has after the counters
+    pub(crate) fn write_fen(&self, output: &mut String) {
+        if let Some(name) = self.fen_name() {
+            output.push(' ');
+            output.push_str(name);
+        }
+    }
+
+    /// Reads the variant from the fields of a FEN after the counters, returning it with the
+    /// number of fields it used. Without a variant field, the FEN is of a Standard board.
+    pub(crate) fn read_fen(fields: &[&str]) -> (Variant, usize) {
+        let variant = [
+            Variant::Antichess,
+            Variant::Horde,
+            Variant::RacingKings,
+            Variant::FogOfWar,
+        ]
+        .into_iter()
+        .find(|variant| fields.first() == variant.fen_name().as_ref());
+        match variant {
+            Some(variant) => (variant, 1),
+            None => (Variant::Standard, 0),
+        }
+    }
+
+    /// Checks the rules of this variant which can only be validated once the board is set up
+    pub(crate) fn validate(&self, board: &Board) -> Result<(), BoardError> {
+        match self {
+            Variant::Standard | Variant::Antichess | Variant::Horde | Variant::FogOfWar => Ok(()),
+            Variant::RacingKings => racing_kings::validate(board),
+        }
+    }
+
+    /// Removes the moves this variant forbids from a list of moves that follow the movement
+    /// rules of the pieces
+    #[inline(always)]
+    pub(crate) fn filter_moves(&self, board: &Board, mvs: &mut Vec<Move>) {
+        match self {
+            Variant::Standard | Variant::Horde | Variant::FogOfWar => (),
+            Variant::Antichess => antichess::retain_forced_captures(board, mvs),
+            Variant::RacingKings => racing_kings::retain_non_checks(board, mvs),
+        }
+    }
+
+    /// Returns the [Outcome] of the game on the given board, or `None` if play can go on
+    pub(crate) fn outcome(&self, board: &Board) -> Option<Outcome> {
+        match self {
+            Variant::Standard => standard_outcome(board),
+            Variant::Antichess => antichess::outcome(board),
+            Variant::Horde => horde::outcome(board),
+            Variant::RacingKings => racing_kings::outcome(board),
+            Variant::FogOfWar => fog_of_war::outcome(board),
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Variant::Standard => "Standard",
+            Variant::Antichess => "Antichess",
+            Variant::Horde => "Horde",
+            Variant::RacingKings => "Racing Kings",
+            Variant::FogOfWar => "Fog of War",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl str::FromStr for Variant {
+    type Err = BoardError;
+
+    /// Parses the name of a variant, ignoring case, spaces and dashes
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .collect::<String>()
+            .to_lowercase();
+        match name.as_str() {
+            "standard" | "chess" => Ok(Variant::Standard),
+            "antichess" | "losingchess" | "giveaway" => Ok(Variant::Antichess),
+            "horde" => Ok(Variant::Horde),
+            "racingkings" => Ok(Variant::RacingKings),
+            "fogofwar" | "darkchess" => Ok(Variant::FogOfWar),
+            _ => Err(ParseError::Variant(s.to_owned()).into()),
+        }
+    }
+}
+
+/// Checkmate and stalemate, shared by every variant with a royal king
+fn standard_outcome(board: &Board) -> Option<Outcome> {
+    if !board.legal_moves().is_empty() {
+        return None;
+    }
+    Some(match board.check() {
+        Check::None => Outcome::Draw(DrawReason::Stalemate),
+        _ => Outcome::Win {
+            winner: !board.color_to_move(),
+            reason: WinReason::Checkmate,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, BoardBuilder, FenOptions, Variant};
+
+    #[test]
+    fn test_variant_perft() {
+        let cases = [
+            (Variant::Horde, vec![8, 128, 1274, 23310]),
+            (Variant::RacingKings, vec![21, 421, 11264]),
+            (Variant::Antichess, vec![20, 400, 8067]),
+            (Variant::FogOfWar, vec![20, 400, 8902]),
+        ];
+        for (variant, nodes) in cases {
+            let mut board = BoardBuilder::for_variant(variant).build().unwrap();
+            for (depth, expected) in nodes.into_iter().enumerate() {
+                assert_eq!(
+                    board.perft(depth + 1),
+                    expected,
+                    "{variant:?} at {}",
+                    depth + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        for variant in [
+            Variant::Standard,
+            Variant::Antichess,
+            Variant::Horde,
+            Variant::RacingKings,
+            Variant::FogOfWar,
+        ] {
+            assert_eq!(variant.to_string().parse::<Variant>().unwrap(), variant);
+        }
+        assert_eq!(
+            "racingkings".parse::<Variant>().unwrap(),
+            Variant::RacingKings
+        );
+        assert!("crazyhouse".parse::<Variant>().is_err());
+    }
+
+    #[test]
+    fn test_fen() {
+        for variant in [
+            Variant::Standard,
+            Variant::Antichess,
+            Variant::Horde,
+            Variant::RacingKings,
+            Variant::FogOfWar,
+        ] {
+            let board = BoardBuilder::for_variant(variant).build().unwrap();
+            let fen = board.to_fen();
+            let read = Board::from_fen(fen.as_str()).unwrap();
+            assert_eq!(read.variant(), variant, "{fen}");
+            assert_eq!(read.to_fen(), fen);
+            let strict = BoardBuilder::from_fen_with_options(&fen, &FenOptions::strict());
+            assert_eq!(strict.unwrap().1, [], "{fen}");
+        }
+        assert_eq!(Board::default().to_fen(), Variant::Standard.starting_fen());
+        assert!(BoardBuilder::for_variant(Variant::Horde)
+            .build()
+            .unwrap()
+            .to_fen()
+            .ends_with(" 0 1 horde"));
+    }
+
+    #[test]
+    fn test_ep_target_after() {
+        let mut board = Board::from_fen("4k3/4p3/8/8/8/8/8/5P2 w - - 0 1 horde").unwrap();
+        // A double push from the first rank can't be captured en passant
+        board.make("f1f3".parse().unwrap()).unwrap();
+        assert_eq!(board.ep_target(), None);
+        board.make("e7e5".parse().unwrap()).unwrap();
+        assert_eq!(board.ep_target(), Some("e6".parse().unwrap()));
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Board, Move, Outcome, PieceKind, WinReason};
+
+pub(super) const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1";
+
+/// Captures are compulsory, so if any move captures only the captures are kept
+#[inline(always)]
+pub(super) fn retain_forced_captures(board: &Board, mvs: &mut Vec<Move>) {
+    let color = board.color_to_move();
+    let is_capture = |mv: &Move| {
+        board[mv.dest].is_color(!color)
+            || board[mv.origin].is_kind(PieceKind::Pawn) && Some(mv.dest) == board.ep_target()
+    };
+    if mvs.iter().any(is_capture) {
+        mvs.retain(is_capture);
+    }
+}
+
+/// The side to move wins if it has no moves left, whether or not it has any pieces
+pub(super) fn outcome(board: &Board) -> Option<Outcome> {
+    if !board.legal_moves().is_empty() {
+        return None;
+    }
+    let color = board.color_to_move();
+    Some(Outcome::Win {
+        winner: color,
+        reason: if board[color].is_empty() {
+            WinReason::OutOfPieces
+        } else {
+            WinReason::OutOfMoves
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, BoardBuilder, Color, Move, Outcome, Variant, WinReason};
+
+    fn antichess(fen: &str) -> Board {
+        BoardBuilder::from_fen(fen)
+            .unwrap()
+            .variant(Variant::Antichess)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_forced_capture() {
+        let board = antichess("rnbqkbnr/p1pppppp/8/1p6/8/4P3/PPPP1PPP/RNBQKBNR w - - 0 2");
+        let mv: Move = "f1b5".parse().unwrap();
+        assert_eq!(board.legal_moves(), vec![mv]);
+    }
+
+    #[test]
+    fn test_king_is_ordinary() {
+        // The king may be captured and may be promoted to
+        let board = antichess("8/P7/8/8/8/8/6K1/7k b - - 0 1");
+        assert_eq!(board.legal_moves(), vec!["h1g2".parse().unwrap()]);
+        let board = antichess("8/P7/8/8/8/8/8/7k w - - 0 1");
+        assert_eq!(board.legal_moves().len(), 5);
+        assert!(board.legal_moves().contains(&"a7a8k".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_outcome() {
+        let mut board = antichess("8/8/8/8/8/8/8/Rr6 w - - 0 1");
+        assert_eq!(board.outcome(), None);
+        board.make("a1b1".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Win {
+                winner: Color::Black,
+                reason: WinReason::OutOfPieces
+            })
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{Board, DrawReason, Outcome, Piece, WinReason};
+
+/// Without check, the game ends when a king is captured. A player left with no moves at all is
+/// stalemated.
+pub(super) fn outcome(board: &Board) -> Option<Outcome> {
+    let color = board.color_to_move();
+    if board[Piece::king(color)].is_empty() {
+        return Some(Outcome::Win {
+            winner: !color,
+            reason: WinReason::KingCaptured,
+        });
+    }
+    if board.legal_moves().is_empty() {
+        return Some(Outcome::Draw(DrawReason::Stalemate));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Board, BoardBuilder, Check, Color, Outcome, Variant, WinReason};
+
+    fn fog_of_war(fen: &str) -> Board {
+        BoardBuilder::from_fen(fen)
+            .unwrap()
+            .variant(Variant::FogOfWar)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_king_capture() {
+        // The king may walk into an attack and castle through one
+        let mut board = fog_of_war("4kr2/8/8/8/8/8/8/4K2R w K - 0 1");
+        assert!(board.legal_moves().contains(&"e1g1".parse().unwrap()));
+
+        board.make("e1f1".parse().unwrap()).unwrap();
+        assert_eq!(board.check(), Check::None);
+        assert_eq!(board.outcome(), None);
+        board.make("f8f1".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Win {
+                winner: Color::Black,
+                reason: WinReason::KingCaptured
+            })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{Board, Color, Outcome, WinReason};
+
+pub(super) const STARTING_FEN: &str =
+    "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1";
+
+/// Black wins by capturing every white piece. White can only win by checkmate.
+pub(super) fn outcome(board: &Board) -> Option<Outcome> {
+    if board[Color::White].is_empty() {
+        return Some(Outcome::Win {
+            winner: Color::Black,
+            reason: WinReason::AllPiecesCaptured,
+        });
+    }
+    super::standard_outcome(board)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoardBuilder, Color, Outcome, Variant, WinReason};
+
+    #[test]
+    fn test_outcome() {
+        let mut board = BoardBuilder::from_fen("4k3/8/8/8/8/8/8/3P4 b - - 0 1")
+            .unwrap()
+            .variant(Variant::Horde)
+            .build()
+            .unwrap();
+        board.make("e8d7".parse().unwrap()).unwrap();
+        board.make("d1d3".parse().unwrap()).unwrap();
+        assert_eq!(board.ep_target(), None);
+        board.make("d7d6".parse().unwrap()).unwrap();
+        board.make("d3d4".parse().unwrap()).unwrap();
+        assert_eq!(board.outcome(), None);
+        board.make("d6c6".parse().unwrap()).unwrap();
+        board.make("d4d5".parse().unwrap()).unwrap();
+        board.make("c6d5".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Win {
+                winner: Color::Black,
+                reason: WinReason::AllPiecesCaptured
+            })
+        );
+    }
+
+    #[test]
+    fn test_double_push() {
+        // White pawns on the first rank can double push, but only White plays the horde
+        let board = BoardBuilder::from_fen("3pk3/8/8/8/8/8/P7/3P4 w - - 0 1")
+            .unwrap()
+            .variant(Variant::Horde)
+            .build()
+            .unwrap();
+        assert!(board.legal_moves().contains(&"d1d3".parse().unwrap()));
+        let mut board = board;
+        board.make("a2a3".parse().unwrap()).unwrap();
+        assert!(!board.legal_moves().contains(&"d8d6".parse().unwrap()));
+    }
+}
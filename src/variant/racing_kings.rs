@@ -0,0 +1,92 @@
+use crate::{
+    Board, BoardError, Check, Color, DrawReason, Move, Outcome, PieceKind, PositionError, WinReason,
+};
+
+pub(super) const STARTING_FEN: &str = "8/8/8/8/8/8/krbnNBRK/qrbnNBRQ w - - 0 1";
+
+/// Neither king may be in check at any point
+pub(super) fn validate(board: &Board) -> Result<(), BoardError> {
+    if board.check() != Check::None {
+        return Err(PositionError::CheckNotAllowed.into());
+    }
+    Ok(())
+}
+
+/// Giving check is illegal, so any move that does is removed
+#[inline(always)]
+pub(super) fn retain_non_checks(board: &Board, mvs: &mut Vec<Move>) {
+    mvs.retain(|mv| !board.gives_check(*mv));
+}
+
+/// Checks whether either king has reached the eighth rank. When white gets there first, black is
+/// given one more move to equalize.
+pub(super) fn outcome(board: &Board) -> Option<Outcome> {
+    let on_goal = |color| matches!(board.king(color), Some(k) if k.rank() == 0);
+    match (on_goal(Color::White), on_goal(Color::Black)) {
+        (true, true) => Some(Outcome::Draw(DrawReason::KingsReachedGoal)),
+        (false, true) => Some(Outcome::Win {
+            winner: Color::Black,
+            reason: WinReason::KingReachedGoal,
+        }),
+        (true, false) => {
+            let black_can_equalize = board.color_to_move() == Color::Black
+                && board
+                    .legal_moves()
+                    .into_iter()
+                    .any(|mv| board[mv.origin].is_kind(PieceKind::King) && mv.dest.rank() == 0);
+            if black_can_equalize {
+                None
+            } else {
+                Some(Outcome::Win {
+                    winner: Color::White,
+                    reason: WinReason::KingReachedGoal,
+                })
+            }
+        }
+        (false, false) => super::standard_outcome(board),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BoardBuilder, Color, DrawReason, Outcome, Variant, WinReason};
+
+    #[test]
+    fn test_rules() {
+        let racing_kings = |fen| {
+            BoardBuilder::from_fen(fen)
+                .unwrap()
+                .variant(Variant::RacingKings)
+                .build()
+        };
+        // Giving check is illegal
+        let mut board = racing_kings("8/8/8/8/8/8/k7/6RK w - - 0 1").unwrap();
+        assert!(!board.legal_moves().contains(&"g1a1".parse().unwrap()));
+        assert!(board.make("g1a1".parse().unwrap()).is_err());
+        assert!(racing_kings("8/8/8/8/8/8/k7/R6K b - - 0 1").is_err());
+
+        // Black may equalize after white reaches the goal
+        let mut board = racing_kings("8/1k4K1/8/8/8/8/8/8 w - - 0 1").unwrap();
+        board.make("g7g8".parse().unwrap()).unwrap();
+        assert_eq!(board.outcome(), None);
+        board.make("b7b8".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Draw(DrawReason::KingsReachedGoal))
+        );
+        board.unmake();
+        board.make("b7c6".parse().unwrap()).unwrap();
+        assert_eq!(
+            board.outcome(),
+            Some(Outcome::Win {
+                winner: Color::White,
+                reason: WinReason::KingReachedGoal
+            })
+        );
+
+        // Otherwise white wins as soon as it gets there
+        let mut board = racing_kings("8/6K1/1k6/8/8/8/8/8 w - - 0 1").unwrap();
+        board.make("g7g8".parse().unwrap()).unwrap();
+        assert!(board.outcome().is_some());
+    }
+}
@@ -1,4 +1,4 @@
-use chb_chess::Board;
+use chb_chess::{Board, ZobristHash};
 use std::collections::HashMap;
 
 mod common;
@@ -7,7 +7,7 @@ mod common;
 fn collision_detection(
     board: &mut Board,
     depth: usize,
-    map: &mut HashMap<u64, HashMap<usize, usize>>,
+    map: &mut HashMap<ZobristHash, HashMap<usize, usize>>,
 ) -> usize {
     let nodes = if depth == 1 {
         board.legal_moves().len()
@@ -40,7 +40,7 @@ fn collision_detection_test() {
     for test in &common::perft_positions() {
         let mut b = Board::from_fen(&test.fen).unwrap();
         for (depth, nodes) in test.depth.iter().zip(test.nodes.iter()) {
-            let mut map: HashMap<u64, HashMap<usize, usize>> = HashMap::default();
+            let mut map: HashMap<ZobristHash, HashMap<usize, usize>> = HashMap::default();
             println!("Testing collision in {} to depth {}", test.name, depth);
             assert_eq!(nodes, &collision_detection(&mut b, *depth, &mut map))
         }